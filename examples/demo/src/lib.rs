@@ -78,6 +78,7 @@ pub fn main() {
             app_info: XrAppInfo {
                 name: "Bevy OXR Demo".into(),
             },
+            ..default()
         })
         //lets add the debug renderer for the controllers
         .add_plugins(OpenXrDebugRenderer)
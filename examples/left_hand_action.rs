@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy_oxr::graphics::XrAppInfo;
+use bevy_oxr::resources::XrSession;
+use bevy_oxr::xr_init::{xr_only, XrSetup};
+use bevy_oxr::xr_input::actions::{
+    ActionHandednes, ActionType, SetupActionSets, XrActionSets, XrBinding,
+};
+use bevy_oxr::xr_input::oculus_touch::subaction_path;
+use bevy_oxr::xr_input::Hand;
+use bevy_oxr::DefaultXrPlugins;
+
+// an action that only exists on the left hand, e.g. for a one handed setup
+fn main() {
+    App::new()
+        .add_plugins(DefaultXrPlugins {
+            app_info: XrAppInfo {
+                name: "Bevy OXR Left Hand Action Example".into(),
+            },
+            ..default()
+        })
+        .add_systems(XrSetup, setup_left_hand_action)
+        .add_systems(Update, read_left_hand_action.run_if(xr_only()))
+        .run();
+}
+
+fn setup_left_hand_action(mut action_sets: ResMut<SetupActionSets>) {
+    let action_set = action_sets.add_action_set("left_hand", "Left Hand".into(), 0);
+    action_set.new_action(
+        "teleport",
+        "Teleport".into(),
+        ActionType::Bool,
+        ActionHandednes::Left,
+    );
+    action_set.suggest_binding(
+        "/interaction_profiles/oculus/touch_controller",
+        &[XrBinding::new("teleport", "/user/hand/left/input/x/click")],
+    );
+    action_set.suggest_binding(
+        "/interaction_profiles/khr/simple_controller",
+        &[XrBinding::new(
+            "teleport",
+            "/user/hand/left/input/select/click",
+        )],
+    );
+}
+
+fn read_left_hand_action(action_sets: Res<XrActionSets>, session: Res<XrSession>) {
+    let Ok(action) = action_sets.get_action_bool("left_hand", "teleport") else {
+        return;
+    };
+    // the right hand path isn't a subaction path of this action, reading it would fail
+    match action.state(&session, subaction_path(Hand::Left)) {
+        Ok(state) if state.changed_since_last_sync && state.current_state => {
+            info!("teleport pressed on the left hand")
+        }
+        Ok(_) => {}
+        Err(err) => warn!("unable to read the teleport action: {}", err),
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+use bevy::render::camera::{ManualTextureView, ManualTextureViewHandle, ManualTextureViews};
+use bevy::render::renderer::RenderDevice;
+
+use crate::graphics;
+use crate::resources::{QuadLayerSubmit, XrFormat, XrLayerOrder, XrLayerSwapchain, XrSwapchain};
+use crate::xr_arc_resource_wrapper;
+use crate::xr_input::trackers::OpenXRTrackingRoot;
+
+// well away from the handles of the eyes
+static NEXT_TEXTURE_VIEW: AtomicU32 = AtomicU32::new(0x4f58_0000);
+
+/// A flat rectangle the runtime composites on its own, e.g. for text or menus that stay sharp
+/// regardless of the render resolution. Faces +Z of the entity's transform and gets a swapchain
+/// of `resolution`, render into it with a camera targeting `texture_view`.
+/// Hidden quads aren't submitted. The size can change, the resolution is fixed once created.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct XrQuadLayer {
+    /// in meters
+    pub size: Vec2,
+    pub resolution: UVec2,
+    /// see [`XrLayerOrder`]
+    pub order: i32,
+    pub texture_view: ManualTextureViewHandle,
+}
+
+impl XrQuadLayer {
+    pub fn new(size: Vec2, resolution: UVec2) -> Self {
+        Self {
+            size,
+            resolution,
+            order: XrLayerOrder::OVERLAY,
+            texture_view: ManualTextureViewHandle(
+                NEXT_TEXTURE_VIEW.fetch_add(1, Ordering::Relaxed),
+            ),
+        }
+    }
+
+    pub fn with_order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+}
+
+pub struct QuadLayerState {
+    pub(crate) swapchain: XrLayerSwapchain,
+    /// relative to the tracking root
+    pub(crate) pose: Transform,
+    pub(crate) size: Vec2,
+    pub(crate) order: i32,
+    pub(crate) texture_view: ManualTextureViewHandle,
+    pub(crate) visible: bool,
+    /// whether the current image is acquired and has to be released at the end of the frame
+    pub(crate) acquired: bool,
+}
+
+impl QuadLayerState {
+    pub(crate) fn submit(&self) -> QuadLayerSubmit {
+        QuadLayerSubmit {
+            swapchain: &self.swapchain,
+            pose: self.pose,
+            size: self.size,
+            order: self.order,
+        }
+    }
+}
+
+xr_arc_resource_wrapper!(XrQuadLayers, Mutex<HashMap<Entity, QuadLayerState>>);
+
+pub fn sync_quad_layers(
+    quad_layers: Res<XrQuadLayers>,
+    swapchain: Res<XrSwapchain>,
+    device: Res<RenderDevice>,
+    format: Res<XrFormat>,
+    tracking_root_query: Query<&GlobalTransform, With<OpenXRTrackingRoot>>,
+    quads: Query<(
+        Entity,
+        &XrQuadLayer,
+        &GlobalTransform,
+        Option<&InheritedVisibility>,
+    )>,
+    mut manual_texture_views: ResMut<ManualTextureViews>,
+    mut failed: Local<HashSet<Entity>>,
+) {
+    let mut layers = quad_layers.lock().unwrap();
+    layers.retain(|entity, state| {
+        let keep = quads.contains(*entity);
+        if !keep {
+            manual_texture_views.remove(&state.texture_view);
+        }
+        keep
+    });
+    failed.retain(|entity| quads.contains(*entity));
+    let root = tracking_root_query
+        .get_single()
+        .map_or(Mat4::IDENTITY, |root| root.compute_matrix().inverse());
+    for (entity, quad, global, visibility) in quads.iter() {
+        if !layers.contains_key(&entity) {
+            if failed.contains(&entity) {
+                continue;
+            }
+            let layer_swapchain = match graphics::create_layer_swapchain(
+                &swapchain,
+                device.wgpu_device(),
+                **format,
+                quad.resolution,
+            ) {
+                Ok(layer_swapchain) => layer_swapchain,
+                Err(err) => {
+                    warn!("unable to create the swapchain of a quad layer: {}", err);
+                    failed.insert(entity);
+                    continue;
+                }
+            };
+            // the render world replaces it with the acquired image every frame
+            manual_texture_views.insert(
+                quad.texture_view,
+                ManualTextureView {
+                    texture_view: layer_swapchain.texture_view().into(),
+                    size: quad.resolution,
+                    format: **format,
+                },
+            );
+            layers.insert(
+                entity,
+                QuadLayerState {
+                    swapchain: layer_swapchain,
+                    pose: Transform::IDENTITY,
+                    size: quad.size,
+                    order: quad.order,
+                    texture_view: quad.texture_view,
+                    visible: true,
+                    acquired: false,
+                },
+            );
+        }
+        let state = layers.get_mut(&entity).unwrap();
+        state.pose = Transform::from_matrix(root * global.compute_matrix());
+        state.size = quad.size;
+        state.order = quad.order;
+        state.visible = visibility.map_or(true, |v| v.get());
+    }
+}
+
+pub fn acquire_quad_layer_images(
+    quad_layers: Res<XrQuadLayers>,
+    format: Res<XrFormat>,
+    mut manual_texture_views: ResMut<ManualTextureViews>,
+) {
+    let _span = info_span!("xr_acquire_quad_layer_images").entered();
+    for state in quad_layers.lock().unwrap().values_mut() {
+        if let Err(err) = state.swapchain.acquire_image() {
+            warn!("unable to acquire the image of a quad layer: {}", err);
+            continue;
+        }
+        state.acquired = true;
+        manual_texture_views.insert(
+            state.texture_view,
+            ManualTextureView {
+                texture_view: state.swapchain.texture_view().into(),
+                size: state.swapchain.resolution(),
+                format: **format,
+            },
+        );
+    }
+}
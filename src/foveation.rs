@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use openxr as xr;
+
+use crate::graphics::extensions::EnabledXrExtensions;
+use crate::resources::{XrInstance, XrSession, XrSwapchain};
+
+/// Fixed foveated rendering level of the swapchain, lowers the resolution towards the edges
+/// of the view. Needs XR_FB_foveation and XR_FB_foveation_configuration,
+/// without them rendering stays at full resolution. Applied again whenever it changes.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum XrFoveationLevel {
+    #[default]
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl XrFoveationLevel {
+    fn to_raw(self) -> xr::sys::FoveationLevelFB {
+        match self {
+            XrFoveationLevel::Off => xr::sys::FoveationLevelFB::NONE,
+            XrFoveationLevel::Low => xr::sys::FoveationLevelFB::LOW,
+            XrFoveationLevel::Medium => xr::sys::FoveationLevelFB::MEDIUM,
+            XrFoveationLevel::High => xr::sys::FoveationLevelFB::HIGH,
+        }
+    }
+}
+
+pub fn supports_foveation(instance: &XrInstance) -> bool {
+    EnabledXrExtensions::from_instance(instance).foveation
+}
+
+/// sets the foveation level of the swapchain, does nothing without the foveation extensions
+pub fn set_foveation_level(
+    instance: &XrInstance,
+    session: &XrSession,
+    swapchain: &XrSwapchain,
+    level: XrFoveationLevel,
+) -> xr::Result<()> {
+    let exts = instance.exts();
+    let (Some(foveation), Some(update_state)) = (
+        exts.fb_foveation.as_ref(),
+        exts.fb_swapchain_update_state.as_ref(),
+    ) else {
+        return Ok(());
+    };
+    let mut level_info = xr::sys::FoveationLevelProfileCreateInfoFB {
+        ty: xr::sys::FoveationLevelProfileCreateInfoFB::TYPE,
+        next: std::ptr::null_mut(),
+        level: level.to_raw(),
+        vertical_offset: 0.0,
+        dynamic: xr::sys::FoveationDynamicFB::DISABLED,
+    };
+    let create_info = xr::sys::FoveationProfileCreateInfoFB {
+        ty: xr::sys::FoveationProfileCreateInfoFB::TYPE,
+        next: &mut level_info as *mut _ as *mut _,
+    };
+    let mut profile = xr::sys::FoveationProfileFB::NULL;
+    let result = unsafe {
+        (foveation.create_foveation_profile)(session.as_raw(), &create_info, &mut profile)
+    };
+    if result.into_raw() < 0 {
+        return Err(result);
+    }
+    let state = xr::sys::SwapchainStateFoveationFB {
+        ty: xr::sys::SwapchainStateFoveationFB::TYPE,
+        next: std::ptr::null_mut(),
+        flags: xr::sys::SwapchainStateFoveationFlagsFB::EMPTY,
+        profile,
+    };
+    let result = unsafe {
+        (update_state.update_swapchain)(swapchain.as_raw(), &state as *const _ as *const _)
+    };
+    // the swapchain keeps the foveation, the profile isn't needed anymore
+    unsafe { (foveation.destroy_foveation_profile)(profile) };
+    match result.into_raw() >= 0 {
+        true => Ok(()),
+        false => Err(result),
+    }
+}
+
+pub fn apply_foveation_level(
+    instance: Res<XrInstance>,
+    session: Res<XrSession>,
+    swapchain: Res<XrSwapchain>,
+    level: Res<XrFoveationLevel>,
+    mut warned: Local<bool>,
+) {
+    if !level.is_changed() {
+        return;
+    }
+    if !supports_foveation(&instance) {
+        if *level != XrFoveationLevel::Off && !*warned {
+            warn!("XR_FB_foveation is not enabled, rendering without foveation");
+            *warned = true;
+        }
+        return;
+    }
+    match set_foveation_level(&instance, &session, &swapchain, *level) {
+        Ok(_) => info!("using foveation level {:?}", *level),
+        Err(err) => warn!("unable to set the foveation level: {}", err),
+    }
+}
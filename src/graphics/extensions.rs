@@ -1,6 +1,9 @@
+use bevy::prelude::Resource;
 use openxr::ExtensionSet;
 use std::ops;
 
+use crate::resources::XrInstance;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct XrExtensions(ExtensionSet);
 impl XrExtensions {
@@ -26,6 +29,15 @@ impl XrExtensions {
         self.0.ext_hand_tracking = false;
         self
     }
+    /// needed for [`HandJointSet::WithForearm`](crate::xr_input::hands::hand_tracking::HandJointSet)
+    pub fn enable_hand_tracking_forearm(&mut self) -> &mut Self {
+        self.0.ultraleap_hand_tracking_forearm = true;
+        self
+    }
+    pub fn disable_hand_tracking_forearm(&mut self) -> &mut Self {
+        self.0.ultraleap_hand_tracking_forearm = false;
+        self
+    }
     pub fn enable_local_floor(&mut self) -> &mut Self {
         self.0.ext_local_floor = true;
         self
@@ -34,7 +46,173 @@ impl XrExtensions {
         self.0.ext_local_floor = false;
         self
     }
+    pub fn enable_vive_cosmos_controller(&mut self) -> &mut Self {
+        self.0.htc_vive_cosmos_controller_interaction = true;
+        self
+    }
+    pub fn disable_vive_cosmos_controller(&mut self) -> &mut Self {
+        self.0.htc_vive_cosmos_controller_interaction = false;
+        self
+    }
+    pub fn enable_vive_focus3_controller(&mut self) -> &mut Self {
+        self.0.htc_vive_focus3_controller_interaction = true;
+        self
+    }
+    pub fn disable_vive_focus3_controller(&mut self) -> &mut Self {
+        self.0.htc_vive_focus3_controller_interaction = false;
+        self
+    }
+    pub fn enable_hp_mixed_reality_controller(&mut self) -> &mut Self {
+        self.0.ext_hp_mixed_reality_controller = true;
+        self
+    }
+    pub fn disable_hp_mixed_reality_controller(&mut self) -> &mut Self {
+        self.0.ext_hp_mixed_reality_controller = false;
+        self
+    }
+    pub fn enable_performance_settings(&mut self) -> &mut Self {
+        self.0.ext_performance_settings = true;
+        self
+    }
+    pub fn disable_performance_settings(&mut self) -> &mut Self {
+        self.0.ext_performance_settings = false;
+        self
+    }
+    /// submits depth with the projection layer so the runtime can reproject better
+    pub fn enable_composition_layer_depth(&mut self) -> &mut Self {
+        self.0.khr_composition_layer_depth = true;
+        self
+    }
+    pub fn disable_composition_layer_depth(&mut self) -> &mut Self {
+        self.0.khr_composition_layer_depth = false;
+        self
+    }
+    /// needed for [`XrFoveationLevel`](crate::foveation::XrFoveationLevel)
+    pub fn enable_foveation(&mut self) -> &mut Self {
+        self.0.fb_foveation = true;
+        self.0.fb_foveation_configuration = true;
+        self.0.fb_swapchain_update_state = true;
+        self
+    }
+    pub fn disable_foveation(&mut self) -> &mut Self {
+        self.0.fb_foveation = false;
+        self.0.fb_foveation_configuration = false;
+        self.0.fb_swapchain_update_state = false;
+        self
+    }
+    /// needed for [`GazePose`](crate::xr_input::eye_gaze::GazePose)
+    pub fn enable_eye_gaze(&mut self) -> &mut Self {
+        self.0.ext_eye_gaze_interaction = true;
+        self
+    }
+    pub fn disable_eye_gaze(&mut self) -> &mut Self {
+        self.0.ext_eye_gaze_interaction = false;
+        self
+    }
+    /// needed to bind vive trackers with [`XrTrackedDevices`](crate::xr_input::tracked_devices::XrTrackedDevices)
+    pub fn enable_vive_tracker(&mut self) -> &mut Self {
+        // the generated extension set doesn't have it
+        if !self.0.other.iter().any(|e| e == VIVE_TRACKER) {
+            self.0.other.push(VIVE_TRACKER.into());
+        }
+        self
+    }
+    pub fn disable_vive_tracker(&mut self) -> &mut Self {
+        self.0.other.retain(|e| e != VIVE_TRACKER);
+        self
+    }
+    pub fn enable_touch_controller_pro(&mut self) -> &mut Self {
+        // not part of the generated extension set yet
+        if !self.0.other.iter().any(|e| e == TOUCH_CONTROLLER_PRO) {
+            self.0.other.push(TOUCH_CONTROLLER_PRO.into());
+        }
+        self
+    }
+    pub fn disable_touch_controller_pro(&mut self) -> &mut Self {
+        self.0.other.retain(|e| e != TOUCH_CONTROLLER_PRO);
+        self
+    }
+}
+
+impl XrExtensions {
+    /// names of the optional extensions this crate uses that are requested here
+    /// but missing from `available`
+    pub fn missing_optional(&self, available: &XrExtensions) -> Vec<String> {
+        let (r, a) = (&self.0, &available.0);
+        let mut missing: Vec<String> = [
+            (r.fb_passthrough, a.fb_passthrough, "XR_FB_passthrough"),
+            (
+                r.ext_hand_tracking,
+                a.ext_hand_tracking,
+                "XR_EXT_hand_tracking",
+            ),
+            (
+                r.ultraleap_hand_tracking_forearm,
+                a.ultraleap_hand_tracking_forearm,
+                "XR_ULTRALEAP_hand_tracking_forearm",
+            ),
+            (r.ext_local_floor, a.ext_local_floor, "XR_EXT_local_floor"),
+            (
+                r.ext_performance_settings,
+                a.ext_performance_settings,
+                "XR_EXT_performance_settings",
+            ),
+            (
+                r.khr_composition_layer_depth,
+                a.khr_composition_layer_depth,
+                "XR_KHR_composition_layer_depth",
+            ),
+            (r.fb_foveation, a.fb_foveation, "XR_FB_foveation"),
+            (
+                r.ext_eye_gaze_interaction,
+                a.ext_eye_gaze_interaction,
+                "XR_EXT_eye_gaze_interaction",
+            ),
+        ]
+        .into_iter()
+        .filter(|(requested, available, _)| *requested && !*available)
+        .map(|(_, _, name)| name.to_string())
+        .collect();
+        missing.extend(r.other.iter().filter(|e| !a.other.contains(e)).cloned());
+        missing
+    }
+}
+
+/// The optional extensions the runtime actually granted, check these before spawning
+/// entities or inserting resources for a feature
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EnabledXrExtensions {
+    pub passthrough: bool,
+    pub hand_tracking: bool,
+    pub hand_tracking_forearm: bool,
+    pub local_floor: bool,
+    pub performance_settings: bool,
+    pub composition_layer_depth: bool,
+    pub foveation: bool,
+    pub eye_gaze: bool,
 }
+
+impl EnabledXrExtensions {
+    pub fn from_instance(instance: &XrInstance) -> Self {
+        let exts = instance.exts();
+        Self {
+            passthrough: exts.fb_passthrough.is_some(),
+            hand_tracking: exts.ext_hand_tracking.is_some(),
+            hand_tracking_forearm: exts.ultraleap_hand_tracking_forearm.is_some(),
+            local_floor: exts.ext_local_floor.is_some(),
+            performance_settings: exts.ext_performance_settings.is_some(),
+            composition_layer_depth: exts.khr_composition_layer_depth.is_some(),
+            foveation: exts.fb_foveation.is_some()
+                && exts.fb_foveation_configuration.is_some()
+                && exts.fb_swapchain_update_state.is_some(),
+            eye_gaze: exts.ext_eye_gaze_interaction.is_some(),
+        }
+    }
+}
+
+const TOUCH_CONTROLLER_PRO: &str = "XR_FB_touch_controller_pro";
+const VIVE_TRACKER: &str = "XR_HTCX_vive_tracker_interaction";
+
 impl From<ExtensionSet> for XrExtensions {
     fn from(value: ExtensionSet) -> Self {
         Self(value)
@@ -50,7 +228,15 @@ impl Default for XrExtensions {
         let mut exts = ExtensionSet::default();
         exts.ext_hand_tracking = true;
         exts.ext_local_floor = true;
-        Self(exts)
+        exts.htc_vive_cosmos_controller_interaction = true;
+        exts.htc_vive_focus3_controller_interaction = true;
+        exts.ext_hp_mixed_reality_controller = true;
+        exts.ext_performance_settings = true;
+        exts.khr_composition_layer_depth = true;
+        let mut exts = Self(exts);
+        exts.enable_foveation();
+        exts.enable_touch_controller_pro();
+        exts
     }
 }
 impl ops::BitAnd for XrExtensions {
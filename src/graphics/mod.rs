@@ -1,20 +1,24 @@
 pub mod extensions;
 mod vulkan;
 
+use bevy::prelude::{Resource, UVec2};
 use bevy::render::renderer::{RenderAdapter, RenderAdapterInfo, RenderDevice, RenderQueue};
 use bevy::window::RawHandleWrapper;
 use wgpu::Instance;
 
 use crate::input::XrInput;
 use crate::resources::{
-    XrEnvironmentBlendMode, XrFormat, XrFrameState, XrFrameWaiter, XrInstance, XrResolution,
-    XrSession, XrSessionRunning, XrSwapchain, XrViews,
+    live_swapchain_textures, Swapchain, SwapchainImages, XrEnvironmentBlendMode, XrFormat,
+    XrFrameState, XrFrameWaiter, XrInstance, XrLayerSwapchain, XrResolution, XrSession,
+    XrSessionRunning, XrSwapchain, XrViews,
 };
 
 use openxr as xr;
 
 use self::extensions::XrExtensions;
 
+/// How the rendered image is composited with the real world, `Additive` is what see through
+/// glasses use, FB passthrough is set up separately through [`crate::passthrough`]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum XrPreferdBlendMode {
     Opaque,
@@ -27,6 +31,45 @@ impl Default for XrPreferdBlendMode {
     }
 }
 
+/// Depth swapchain formats in order of preference, the first one the runtime supports gets used.
+/// If none of them is supported the first depth format the runtime lists is used instead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XrPreferdDepthFormats(pub Vec<wgpu::TextureFormat>);
+impl Default for XrPreferdDepthFormats {
+    fn default() -> Self {
+        Self(vec![
+            wgpu::TextureFormat::Depth32Float,
+            wgpu::TextureFormat::Depth24PlusStencil8,
+            wgpu::TextureFormat::Depth16Unorm,
+        ])
+    }
+}
+
+/// Color swapchain formats in order of preference, tried after the format of the window.
+/// If none of them is supported the first color format the runtime lists is used instead,
+/// every attempt is logged so it's easy to tell why colors look off.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XrPreferdColorFormats(pub Vec<wgpu::TextureFormat>);
+impl Default for XrPreferdColorFormats {
+    fn default() -> Self {
+        Self(vec![
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::TextureFormat::Bgra8UnormSrgb,
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::TextureFormat::Bgra8Unorm,
+        ])
+    }
+}
+
+/// API layers to enable at instance creation, e.g. `XR_APILAYER_LUNARG_core_validation`.
+/// Layers that aren't installed are skipped with a warning.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnabledApiLayers(pub Vec<String>);
+
+/// The API layers that were actually enabled
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct XrApiLayers(pub Vec<String>);
+
 #[derive(Clone, Debug)]
 pub struct XrAppInfo {
     pub name: String,
@@ -43,6 +86,9 @@ pub fn initialize_xr_graphics(
     window: Option<RawHandleWrapper>,
     reqeusted_extensions: XrExtensions,
     prefered_blend_mode: XrPreferdBlendMode,
+    prefered_depth_formats: XrPreferdDepthFormats,
+    prefered_color_formats: XrPreferdColorFormats,
+    api_layers: EnabledApiLayers,
     app_info: XrAppInfo,
 ) -> anyhow::Result<(
     RenderDevice,
@@ -61,8 +107,71 @@ pub fn initialize_xr_graphics(
     XrInput,
     XrViews,
     XrFrameState,
+    XrApiLayers,
 )> {
-    vulkan::initialize_xr_graphics(window, reqeusted_extensions, prefered_blend_mode, app_info)
+    vulkan::initialize_xr_graphics(
+        window,
+        reqeusted_extensions,
+        prefered_blend_mode,
+        prefered_depth_formats,
+        prefered_color_formats,
+        api_layers,
+        app_info,
+    )
+}
+
+/// creates the swapchain of an extra composition layer, on the session `swapchain` belongs to
+pub(crate) fn create_layer_swapchain(
+    swapchain: &Swapchain,
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    resolution: UVec2,
+) -> xr::Result<XrLayerSwapchain> {
+    match swapchain {
+        Swapchain::Vulkan(swapchain) => Ok(XrLayerSwapchain::Vulkan(
+            vulkan::create_layer_swapchain(&swapchain.session, device, format, resolution)?,
+        )),
+    }
+}
+
+/// replaces the images of the projection with ones of `resolution`, no image may be acquired.
+/// blocks until the gpu is done with the old ones
+pub(crate) fn recreate_swapchain_images(
+    swapchain: &Swapchain,
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    resolution: UVec2,
+) -> xr::Result<()> {
+    match swapchain {
+        Swapchain::Vulkan(swapchain) => {
+            let depth_format = swapchain
+                .images
+                .read()
+                .unwrap()
+                .depth
+                .as_ref()
+                .and(swapchain.depth_format);
+            let images = vulkan::create_swapchain_images(
+                &swapchain.session,
+                device,
+                format,
+                depth_format,
+                resolution,
+                swapchain.view_count,
+            )?;
+            device.poll(wgpu::Maintain::Wait);
+            let mut old_images = swapchain.images.write().unwrap();
+            let expected = live_swapchain_textures() - texture_count(&*old_images);
+            *old_images = images;
+            // the textures of the old images have to be freed with them
+            debug_assert_eq!(live_swapchain_textures(), expected);
+        }
+    }
+    Ok(())
+}
+
+fn texture_count<G: xr::Graphics>(images: &SwapchainImages<G>) -> usize {
+    images.buffers.len() + images.depth.as_ref().map_or(0, |d| d.buffers.len())
 }
 
 pub fn xr_entry() -> anyhow::Result<xr::Entry> {
@@ -1,6 +1,6 @@
 use std::ffi::{c_void, CString};
 use std::sync::atomic::AtomicBool;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 
 use anyhow::Context;
 use ash::vk::{self, Handle};
@@ -15,17 +15,24 @@ use xr::EnvironmentBlendMode;
 use crate::graphics::extensions::XrExtensions;
 use crate::input::XrInput;
 use crate::resources::{
-    Swapchain, SwapchainInner, XrEnvironmentBlendMode, XrFormat, XrFrameState, XrFrameWaiter,
-    XrInstance, XrResolution, XrSession, XrSessionRunning, XrSwapchain, XrViews,
+    create_render_views, DepthSwapchain, LayerSwapchain, Swapchain, SwapchainImages,
+    SwapchainInner, SwapchainTextures, XrEnvironmentBlendMode, XrFormat, XrFrameState,
+    XrFrameWaiter, XrInstance, XrResolution, XrSession, XrSessionRunning, XrSwapchain, XrViews,
 };
 use crate::VIEW_TYPE;
 
-use super::{XrAppInfo, XrPreferdBlendMode};
+use super::{
+    EnabledApiLayers, XrApiLayers, XrAppInfo, XrPreferdBlendMode, XrPreferdColorFormats,
+    XrPreferdDepthFormats,
+};
 
 pub fn initialize_xr_graphics(
     window: Option<RawHandleWrapper>,
     reqeusted_extensions: XrExtensions,
     prefered_blend_mode: XrPreferdBlendMode,
+    prefered_depth_formats: XrPreferdDepthFormats,
+    prefered_color_formats: XrPreferdColorFormats,
+    api_layers: EnabledApiLayers,
     app_info: XrAppInfo,
 ) -> anyhow::Result<(
     RenderDevice,
@@ -44,6 +51,7 @@ pub fn initialize_xr_graphics(
     XrInput,
     XrViews,
     XrFrameState,
+    XrApiLayers,
 )> {
     use wgpu_hal::{api::Vulkan as V, Api};
 
@@ -56,6 +64,9 @@ pub fn initialize_xr_graphics(
     assert!(available_extensions.raw().khr_vulkan_enable2);
     info!("available xr exts: {:#?}", available_extensions);
 
+    for missing in reqeusted_extensions.missing_optional(&available_extensions) {
+        warn!("the runtime doesn't support {}, skipping it", missing);
+    }
     let mut enabled_extensions: xr::ExtensionSet =
         (available_extensions & reqeusted_extensions).into();
     enabled_extensions.khr_vulkan_enable2 = true;
@@ -66,6 +77,20 @@ pub fn initialize_xr_graphics(
 
     let available_layers = xr_entry.enumerate_layers()?;
     info!("available xr layers: {:#?}", available_layers);
+    let enabled_layers: Vec<String> = api_layers
+        .0
+        .into_iter()
+        .filter(|layer| {
+            let available = available_layers.iter().any(|l| &l.layer_name == layer);
+            if !available {
+                warn!("XR api layer {} is not available, skipping it", layer);
+            }
+            available
+        })
+        .collect();
+    if !enabled_layers.is_empty() {
+        info!("enabling xr layers: {:?}", enabled_layers);
+    }
 
     let xr_instance = xr_entry.create_instance(
         &xr::ApplicationInfo {
@@ -74,7 +99,10 @@ pub fn initialize_xr_graphics(
             ..Default::default()
         },
         &enabled_extensions,
-        &[],
+        &enabled_layers
+            .iter()
+            .map(|layer| layer.as_str())
+            .collect::<Vec<_>>(),
     )?;
     info!("created instance");
     let instance_props = xr_instance.properties()?;
@@ -93,21 +121,29 @@ pub fn initialize_xr_graphics(
     );
 
     let blend_modes = xr_instance.enumerate_environment_blend_modes(xr_system_id, VIEW_TYPE)?;
-    let blend_mode: EnvironmentBlendMode = match prefered_blend_mode {
-        XrPreferdBlendMode::Opaque if blend_modes.contains(&EnvironmentBlendMode::OPAQUE) => {
-            EnvironmentBlendMode::OPAQUE
-        }
-        XrPreferdBlendMode::Additive if blend_modes.contains(&EnvironmentBlendMode::ADDITIVE) => {
-            EnvironmentBlendMode::ADDITIVE
-        }
-        XrPreferdBlendMode::AlphaBlend
-            if blend_modes.contains(&EnvironmentBlendMode::ALPHA_BLEND) =>
-        {
-            EnvironmentBlendMode::ALPHA_BLEND
-        }
-        _ => EnvironmentBlendMode::OPAQUE,
+    let prefered = match prefered_blend_mode {
+        XrPreferdBlendMode::Opaque => EnvironmentBlendMode::OPAQUE,
+        XrPreferdBlendMode::Additive => EnvironmentBlendMode::ADDITIVE,
+        XrPreferdBlendMode::AlphaBlend => EnvironmentBlendMode::ALPHA_BLEND,
     };
-
+    let blend_mode = if blend_modes.contains(&prefered) {
+        prefered
+    } else {
+        // runtimes of see through glasses may not list opaque at all
+        let fallback = match blend_modes.contains(&EnvironmentBlendMode::OPAQUE) {
+            true => EnvironmentBlendMode::OPAQUE,
+            false => blend_modes
+                .first()
+                .copied()
+                .unwrap_or(EnvironmentBlendMode::OPAQUE),
+        };
+        warn!(
+            "the runtime doesn't support the {:?} blend mode, using {:?}",
+            prefered, fallback
+        );
+        fallback
+    };
+    info!("using blend mode {:?}", blend_mode);
 
     #[cfg(not(target_os = "android"))]
     let vk_target_version = vk::make_api_version(0, 1, 2, 0);
@@ -319,36 +355,108 @@ pub fn initialize_xr_graphics(
             .create_surface(&handle)
             .expect("Failed to create wgpu surface")
     });
-    let swapchain_format = surface
-        .as_ref()
-        .map(|surface| surface.get_capabilities(&wgpu_adapter).formats[0])
-        .unwrap_or(wgpu::TextureFormat::Rgba8UnormSrgb);
+    let window_format = surface.as_ref().and_then(|surface| {
+        surface
+            .get_capabilities(&wgpu_adapter)
+            .formats
+            .first()
+            .copied()
+    });
+
+    let runtime_formats = session.enumerate_swapchain_formats()?;
+    let swapchain_format =
+        pick_color_format(window_format, &prefered_color_formats, &runtime_formats).ok_or_else(
+            || anyhow::anyhow!("the runtime doesn't support any usable color swapchain format"),
+        )?;
+    info!("using color format {:?}", swapchain_format);
+    let depth_format = pick_depth_format(&prefered_depth_formats, &runtime_formats);
+    match depth_format {
+        Some(format) => info!("using depth format {:?}", format),
+        None => warn!("the runtime doesn't support any depth swapchain format"),
+    }
 
     let resolution = uvec2(
         views[0].recommended_image_rect_width,
         views[0].recommended_image_rect_height,
     );
+    // one array layer per view, two for stereo
+    let view_count = views.len() as u32;
+
+    // depth is only submitted with `XR_KHR_composition_layer_depth`
+    let submitted_depth_format =
+        depth_format.filter(|_| xr_instance.exts().khr_composition_layer_depth.is_some());
+    let images = create_swapchain_images(
+        &session,
+        &wgpu_device,
+        swapchain_format,
+        submitted_depth_format,
+        resolution,
+        view_count,
+    )?;
+    if images.depth.is_some() {
+        info!("submitting depth with the projection layer");
+    }
 
-    let handle = session
-        .create_swapchain(&xr::SwapchainCreateInfo {
-            create_flags: xr::SwapchainCreateFlags::EMPTY,
-            usage_flags: xr::SwapchainUsageFlags::COLOR_ATTACHMENT
-                | xr::SwapchainUsageFlags::SAMPLED,
-            format: wgpu_to_vulkan(swapchain_format).as_raw() as _,
-            // The Vulkan graphics pipeline we create is not set up for multisampling,
-            // so we hardcode this to 1. If we used a proper multisampling setup, we
-            // could set this to `views[0].recommended_swapchain_sample_count`.
-            sample_count: 1,
-            width: resolution.x,
-            height: resolution.y,
-            face_count: 1,
-            array_size: 2,
-            mip_count: 1,
+    Ok((
+        wgpu_device.into(),
+        RenderQueue(Arc::new(wgpu_queue)),
+        RenderAdapterInfo(wgpu_adapter.get_info()),
+        RenderAdapter(Arc::new(wgpu_adapter)),
+        wgpu_instance,
+        xr_instance.clone().into(),
+        session.clone().into_any_graphics().into(),
+        blend_mode.into(),
+        resolution.into(),
+        swapchain_format.into(),
+        AtomicBool::new(false).into(),
+        Mutex::new(frame_wait).into(),
+        Swapchain::Vulkan(SwapchainInner {
+            session: session.clone(),
+            stream: Mutex::new(frame_stream),
+            images: RwLock::new(images),
+            depth_format,
+            max_sample_count: views[0].max_swapchain_sample_count,
+            view_count,
+        })
+        .into(),
+        XrInput::new(xr_instance, session.into_any_graphics())?,
+        Mutex::default().into(),
+        Mutex::new(xr::FrameState {
+            predicted_display_time: xr::Time::from_nanos(1),
+            predicted_display_period: xr::Duration::from_nanos(1),
+            should_render: true,
         })
-        .unwrap();
-    let images = handle.enumerate_images().unwrap();
+        .into(),
+        XrApiLayers(enabled_layers),
+    ))
+}
 
-    let buffers = images
+/// the color and, with a `depth_format`, depth images of the projection layer
+pub(crate) fn create_swapchain_images(
+    session: &xr::Session<xr::Vulkan>,
+    wgpu_device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    resolution: UVec2,
+    view_count: u32,
+) -> xr::Result<SwapchainImages<xr::Vulkan>> {
+    use wgpu_hal::{api::Vulkan as V, Api};
+
+    let handle = session.create_swapchain(&xr::SwapchainCreateInfo {
+        create_flags: xr::SwapchainCreateFlags::EMPTY,
+        usage_flags: xr::SwapchainUsageFlags::COLOR_ATTACHMENT | xr::SwapchainUsageFlags::SAMPLED,
+        format: wgpu_to_vulkan(format).as_raw() as _,
+        // bevy resolves its multisampled main texture into this, see `XrMsaaSetting`
+        sample_count: 1,
+        width: resolution.x,
+        height: resolution.y,
+        face_count: 1,
+        array_size: view_count,
+        mip_count: 1,
+    })?;
+    let images = handle.enumerate_images()?;
+
+    let buffers: SwapchainTextures = images
         .into_iter()
         .map(|color_image| {
             let color_image = vk::Image::from_raw(color_image);
@@ -360,12 +468,12 @@ pub fn initialize_xr_graphics(
                         size: wgpu::Extent3d {
                             width: resolution.x,
                             height: resolution.y,
-                            depth_or_array_layers: 2,
+                            depth_or_array_layers: view_count,
                         },
                         mip_level_count: 1,
                         sample_count: 1,
                         dimension: wgpu::TextureDimension::D2,
-                        format: swapchain_format,
+                        format,
                         usage: wgpu_hal::TextureUses::COLOR_TARGET
                             | wgpu_hal::TextureUses::COPY_DST,
                         memory_flags: wgpu_hal::MemoryFlags::empty(),
@@ -382,12 +490,12 @@ pub fn initialize_xr_graphics(
                         size: wgpu::Extent3d {
                             width: resolution.x,
                             height: resolution.y,
-                            depth_or_array_layers: 2,
+                            depth_or_array_layers: view_count,
                         },
                         mip_level_count: 1,
                         sample_count: 1,
                         dimension: wgpu::TextureDimension::D2,
-                        format: swapchain_format,
+                        format,
                         usage: wgpu::TextureUsages::RENDER_ATTACHMENT
                             | wgpu::TextureUsages::COPY_DST,
                         view_formats: &[],
@@ -398,35 +506,230 @@ pub fn initialize_xr_graphics(
         })
         .collect();
 
-    Ok((
-        wgpu_device.into(),
-        RenderQueue(Arc::new(wgpu_queue)),
-        RenderAdapterInfo(wgpu_adapter.get_info()),
-        RenderAdapter(Arc::new(wgpu_adapter)),
-        wgpu_instance,
-        xr_instance.clone().into(),
-        session.clone().into_any_graphics().into(),
-        blend_mode.into(),
-        resolution.into(),
-        swapchain_format.into(),
-        AtomicBool::new(false).into(),
-        Mutex::new(frame_wait).into(),
-        Swapchain::Vulkan(SwapchainInner {
-            stream: Mutex::new(frame_stream),
-            handle: Mutex::new(handle),
-            buffers,
-            image_index: Mutex::new(0),
+    let depth = match depth_format {
+        Some(depth_format) => {
+            let handle = session.create_swapchain(&xr::SwapchainCreateInfo {
+                create_flags: xr::SwapchainCreateFlags::EMPTY,
+                usage_flags: xr::SwapchainUsageFlags::DEPTH_STENCIL_ATTACHMENT
+                    | xr::SwapchainUsageFlags::TRANSFER_DST,
+                format: wgpu_to_vulkan(depth_format).as_raw() as _,
+                sample_count: 1,
+                width: resolution.x,
+                height: resolution.y,
+                face_count: 1,
+                array_size: view_count,
+                mip_count: 1,
+            })?;
+            let buffers = handle
+                .enumerate_images()?
+                .into_iter()
+                .map(|depth_image| {
+                    let depth_image = vk::Image::from_raw(depth_image);
+                    let size = wgpu::Extent3d {
+                        width: resolution.x,
+                        height: resolution.y,
+                        depth_or_array_layers: view_count,
+                    };
+                    let wgpu_hal_texture = unsafe {
+                        <V as Api>::Device::texture_from_raw(
+                            depth_image,
+                            &wgpu_hal::TextureDescriptor {
+                                label: Some("VR Depth Swapchain"),
+                                size,
+                                mip_level_count: 1,
+                                sample_count: 1,
+                                dimension: wgpu::TextureDimension::D2,
+                                format: depth_format,
+                                usage: wgpu_hal::TextureUses::DEPTH_STENCIL_WRITE
+                                    | wgpu_hal::TextureUses::COPY_DST,
+                                memory_flags: wgpu_hal::MemoryFlags::empty(),
+                                view_formats: vec![],
+                            },
+                            None,
+                        )
+                    };
+                    unsafe {
+                        wgpu_device.create_texture_from_hal::<V>(
+                            wgpu_hal_texture,
+                            &wgpu::TextureDescriptor {
+                                label: Some("VR Depth Swapchain"),
+                                size,
+                                mip_level_count: 1,
+                                sample_count: 1,
+                                dimension: wgpu::TextureDimension::D2,
+                                format: depth_format,
+                                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                                    | wgpu::TextureUsages::COPY_DST,
+                                view_formats: &[],
+                            },
+                        )
+                    }
+                })
+                .collect();
+            Some(DepthSwapchain {
+                handle: Mutex::new(handle),
+                buffers,
+                image_index: Mutex::new(0),
+                planes: Mutex::default(),
+            })
+        }
+        _ => None,
+    };
+
+    Ok(SwapchainImages {
+        render_views: create_render_views(&buffers, view_count),
+        buffers,
+        depth,
+        handle: Mutex::new(handle),
+        image_index: Mutex::new(0),
+        resolution,
+    })
+}
+
+/// a single layer color swapchain for a composition layer, e.g. a quad
+pub fn create_layer_swapchain(
+    session: &xr::Session<xr::Vulkan>,
+    wgpu_device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    resolution: UVec2,
+) -> xr::Result<LayerSwapchain<xr::Vulkan>> {
+    use wgpu_hal::{api::Vulkan as V, Api};
+
+    let handle = session.create_swapchain(&xr::SwapchainCreateInfo {
+        create_flags: xr::SwapchainCreateFlags::EMPTY,
+        usage_flags: xr::SwapchainUsageFlags::COLOR_ATTACHMENT | xr::SwapchainUsageFlags::SAMPLED,
+        format: wgpu_to_vulkan(format).as_raw() as _,
+        sample_count: 1,
+        width: resolution.x,
+        height: resolution.y,
+        face_count: 1,
+        array_size: 1,
+        mip_count: 1,
+    })?;
+    let size = wgpu::Extent3d {
+        width: resolution.x,
+        height: resolution.y,
+        depth_or_array_layers: 1,
+    };
+    let buffers = handle
+        .enumerate_images()?
+        .into_iter()
+        .map(|color_image| {
+            let color_image = vk::Image::from_raw(color_image);
+            let wgpu_hal_texture = unsafe {
+                <V as Api>::Device::texture_from_raw(
+                    color_image,
+                    &wgpu_hal::TextureDescriptor {
+                        label: Some("VR Layer Swapchain"),
+                        size,
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format,
+                        usage: wgpu_hal::TextureUses::COLOR_TARGET
+                            | wgpu_hal::TextureUses::COPY_DST,
+                        memory_flags: wgpu_hal::MemoryFlags::empty(),
+                        view_formats: vec![],
+                    },
+                    None,
+                )
+            };
+            unsafe {
+                wgpu_device.create_texture_from_hal::<V>(
+                    wgpu_hal_texture,
+                    &wgpu::TextureDescriptor {
+                        label: Some("VR Layer Swapchain"),
+                        size,
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format,
+                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                            | wgpu::TextureUsages::COPY_DST,
+                        view_formats: &[],
+                    },
+                )
+            }
         })
-        .into(),
-        XrInput::new(xr_instance, session.into_any_graphics())?,
-        Mutex::default().into(),
-        Mutex::new(xr::FrameState {
-            predicted_display_time: xr::Time::from_nanos(1),
-            predicted_display_period: xr::Duration::from_nanos(1),
-            should_render: true,
+        .collect();
+    Ok(LayerSwapchain {
+        handle: Mutex::new(handle),
+        buffers,
+        image_index: Mutex::new(0),
+        resolution,
+    })
+}
+
+/// the first preferred format the runtime supports, otherwise the runtime's own first depth format
+fn pick_depth_format(
+    prefered: &XrPreferdDepthFormats,
+    runtime_formats: &[u32],
+) -> Option<wgpu::TextureFormat> {
+    let supported: Vec<wgpu::TextureFormat> = runtime_formats
+        .iter()
+        .filter_map(|f| vulkan_to_wgpu(vk::Format::from_raw(*f as _)))
+        .filter(|f| f.is_depth_stencil_format())
+        .collect();
+    prefered
+        .0
+        .iter()
+        .find(|f| supported.contains(f))
+        .or_else(|| {
+            if !prefered.0.is_empty() {
+                warn!(
+                    "none of the preferred depth formats {:?} are supported, falling back to the runtime's choice",
+                    prefered.0
+                );
+            }
+            supported.first()
         })
-        .into(),
-    ))
+        .copied()
+}
+
+/// tries the window format, then the preferred formats, then the first color format the runtime lists
+fn pick_color_format(
+    window_format: Option<wgpu::TextureFormat>,
+    prefered: &XrPreferdColorFormats,
+    runtime_formats: &[u32],
+) -> Option<wgpu::TextureFormat> {
+    let supported: Vec<wgpu::TextureFormat> = runtime_formats
+        .iter()
+        .filter_map(|f| vulkan_to_wgpu(vk::Format::from_raw(*f as _)))
+        .filter(|f| !f.is_depth_stencil_format())
+        .collect();
+    for format in window_format.iter().chain(prefered.0.iter()) {
+        if supported.contains(format) {
+            return Some(*format);
+        }
+        info!("color format {:?} is not supported by the runtime", format);
+    }
+    let fallback = supported.first().copied();
+    if let Some(format) = fallback {
+        warn!(
+            "none of the preferred color formats are supported, falling back to the runtime's choice {:?}",
+            format
+        );
+    }
+    fallback
+}
+
+fn vulkan_to_wgpu(format: vk::Format) -> Option<wgpu::TextureFormat> {
+    use vk::Format;
+    Some(match format {
+        Format::S8_UINT => wgpu::TextureFormat::Stencil8,
+        Format::D16_UNORM => wgpu::TextureFormat::Depth16Unorm,
+        Format::X8_D24_UNORM_PACK32 => wgpu::TextureFormat::Depth24Plus,
+        Format::D24_UNORM_S8_UINT => wgpu::TextureFormat::Depth24PlusStencil8,
+        Format::D32_SFLOAT => wgpu::TextureFormat::Depth32Float,
+        Format::D32_SFLOAT_S8_UINT => wgpu::TextureFormat::Depth32FloatStencil8,
+        Format::R8G8B8A8_UNORM => wgpu::TextureFormat::Rgba8Unorm,
+        Format::R8G8B8A8_SRGB => wgpu::TextureFormat::Rgba8UnormSrgb,
+        Format::B8G8R8A8_UNORM => wgpu::TextureFormat::Bgra8Unorm,
+        Format::B8G8R8A8_SRGB => wgpu::TextureFormat::Bgra8UnormSrgb,
+        Format::R16G16B16A16_SFLOAT => wgpu::TextureFormat::Rgba16Float,
+        Format::A2B10G10R10_UNORM_PACK32 => wgpu::TextureFormat::Rgb10a2Unorm,
+        _ => return None,
+    })
 }
 
 fn wgpu_to_vulkan(format: wgpu::TextureFormat) -> vk::Format {
@@ -461,7 +764,7 @@ fn wgpu_to_vulkan(format: wgpu::TextureFormat) -> vk::Format {
         wgpu::TextureFormat::Bgra8Unorm => Format::B8G8R8A8_UNORM,
         wgpu::TextureFormat::Bgra8UnormSrgb => Format::B8G8R8A8_SRGB,
         wgpu::TextureFormat::Rgb9e5Ufloat => Format::E5B9G9R9_UFLOAT_PACK32, // this might be the wrong type??? i can't tell
-        wgpu::TextureFormat::Rgb10a2Unorm => Format::A2R10G10B10_UNORM_PACK32,
+        wgpu::TextureFormat::Rgb10a2Unorm => Format::A2B10G10R10_UNORM_PACK32,
         wgpu::TextureFormat::Rg11b10Float => panic!("this texture type invokes nothing but fear within my soul and i don't think vulkan has a proper type for this"),
         wgpu::TextureFormat::Rg32Uint => Format::R32G32_UINT,
         wgpu::TextureFormat::Rg32Sint => Format::R32G32_SINT,
@@ -494,3 +797,80 @@ fn wgpu_to_vulkan(format: wgpu::TextureFormat) -> vk::Format {
         _ => panic!("fuck no")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime_formats(formats: &[vk::Format]) -> Vec<u32> {
+        formats.iter().map(|f| f.as_raw() as u32).collect()
+    }
+
+    #[test]
+    fn window_format_wins() {
+        let formats = runtime_formats(&[vk::Format::R8G8B8A8_SRGB, vk::Format::B8G8R8A8_SRGB]);
+        assert_eq!(
+            pick_color_format(
+                Some(wgpu::TextureFormat::Bgra8UnormSrgb),
+                &XrPreferdColorFormats::default(),
+                &formats
+            ),
+            Some(wgpu::TextureFormat::Bgra8UnormSrgb)
+        );
+    }
+
+    #[test]
+    fn preferred_formats_are_tried_in_order() {
+        let formats = runtime_formats(&[vk::Format::B8G8R8A8_UNORM, vk::Format::R8G8B8A8_SRGB]);
+        // the window format isn't supported, so the first supported preference is used
+        assert_eq!(
+            pick_color_format(
+                Some(wgpu::TextureFormat::Rgba16Float),
+                &XrPreferdColorFormats::default(),
+                &formats
+            ),
+            Some(wgpu::TextureFormat::Rgba8UnormSrgb)
+        );
+        let prefered = XrPreferdColorFormats(vec![
+            wgpu::TextureFormat::Bgra8UnormSrgb,
+            wgpu::TextureFormat::Bgra8Unorm,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+        ]);
+        assert_eq!(
+            pick_color_format(None, &prefered, &formats),
+            Some(wgpu::TextureFormat::Bgra8Unorm)
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_first_runtime_color_format() {
+        let formats = runtime_formats(&[
+            vk::Format::D32_SFLOAT,
+            vk::Format::R16G16B16A16_SFLOAT,
+            vk::Format::R8G8B8A8_UNORM,
+        ]);
+        let prefered = XrPreferdColorFormats(vec![wgpu::TextureFormat::Bgra8UnormSrgb]);
+        assert_eq!(
+            pick_color_format(None, &prefered, &formats),
+            Some(wgpu::TextureFormat::Rgba16Float)
+        );
+    }
+
+    #[test]
+    fn no_runtime_formats() {
+        assert_eq!(
+            pick_color_format(
+                Some(wgpu::TextureFormat::Rgba8UnormSrgb),
+                &XrPreferdColorFormats::default(),
+                &[]
+            ),
+            None
+        );
+        // formats wgpu has no equivalent for are ignored
+        let formats = runtime_formats(&[vk::Format::R5G6B5_UNORM_PACK16]);
+        assert_eq!(
+            pick_color_format(None, &XrPreferdColorFormats::default(), &formats),
+            None
+        );
+    }
+}
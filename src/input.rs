@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use bevy::prelude::*;
+use bevy::render::extract_resource::ExtractResource;
 use openxr as xr;
 use xr::{FrameState, FrameWaiter, ViewConfigurationType};
 
@@ -12,6 +13,19 @@ pub struct XrInput {
     //pub left_space: Arc<xr::Space>,
     pub stage: Arc<xr::Space>,
     pub head: Arc<xr::Space>,
+    /// the reference space `stage` was created from
+    pub stage_type: xr::ReferenceSpaceType,
+    /// pose of `stage` inside its reference space, changed by recentering
+    pub stage_offset: Transform,
+}
+
+// the stage gets recreated when recentering, the render world has to submit with the same one
+impl ExtractResource for XrInput {
+    type Source = Self;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        source.clone()
+    }
 }
 
 impl XrInput {
@@ -50,14 +64,11 @@ impl XrInput {
         //     xr::Posef::IDENTITY,
         // )?;
 
-        let stage = match instance.exts().ext_local_floor {
-            None => session
-                .create_reference_space(xr::ReferenceSpaceType::STAGE, xr::Posef::IDENTITY)?,
-            Some(_) => session.create_reference_space(
-                xr::ReferenceSpaceType::LOCAL_FLOOR_EXT,
-                xr::Posef::IDENTITY,
-            )?,
+        let stage_type = match instance.exts().ext_local_floor {
+            None => xr::ReferenceSpaceType::STAGE,
+            Some(_) => xr::ReferenceSpaceType::LOCAL_FLOOR_EXT,
         };
+        let stage = session.create_reference_space(stage_type, xr::Posef::IDENTITY)?;
         let head =
             session.create_reference_space(xr::ReferenceSpaceType::VIEW, xr::Posef::IDENTITY)?;
         // let y = stage
@@ -81,6 +92,8 @@ impl XrInput {
             // left_space: Arc::new(left_space),
             stage: Arc::new(stage),
             head: Arc::new(head),
+            stage_type,
+            stage_offset: Transform::IDENTITY,
         })
     }
 }
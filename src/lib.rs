@@ -1,6 +1,11 @@
+pub mod composition_layers;
+pub mod foveation;
 pub mod graphics;
 pub mod input;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod passthrough;
+pub mod performance;
 pub mod resource_macros;
 pub mod resources;
 pub mod xr_init;
@@ -12,40 +17,114 @@ use crate::xr_init::RenderRestartPlugin;
 use crate::xr_input::hands::hand_tracking::DisableHandTracking;
 use crate::xr_input::oculus_touch::ActionSets;
 use bevy::app::{AppExit, PluginGroupBuilder};
-use bevy::ecs::system::SystemState;
+use bevy::ecs::system::{SystemParam, SystemState};
 use bevy::prelude::*;
-use bevy::render::camera::{ManualTextureView, ManualTextureViewHandle, ManualTextureViews};
+use bevy::render::camera::{
+    CameraUpdateSystem, ExtractedCamera, ManualTextureView, ManualTextureViewHandle,
+    ManualTextureViews, NormalizedRenderTarget,
+};
+use bevy::render::extract_resource::ExtractResourcePlugin;
 use bevy::render::pipelined_rendering::PipelinedRenderingPlugin;
-use bevy::render::renderer::{render_system, RenderInstance};
+use bevy::render::renderer::{render_system, RenderDevice, RenderInstance, RenderQueue};
 use bevy::render::settings::RenderCreation;
+use bevy::render::view::ViewDepthTexture;
 use bevy::render::{Render, RenderApp, RenderPlugin, RenderSet};
+use bevy::transform::TransformSystem;
 use bevy::window::{PresentMode, PrimaryWindow, RawHandleWrapper};
-use graphics::extensions::XrExtensions;
-use graphics::{XrAppInfo, XrPreferdBlendMode};
+use composition_layers::{
+    acquire_quad_layer_images, sync_quad_layers, QuadLayerState, XrQuadLayers,
+};
+use foveation::{apply_foveation_level, XrFoveationLevel};
+use graphics::extensions::{EnabledXrExtensions, XrExtensions};
+use graphics::{
+    EnabledApiLayers, XrAppInfo, XrPreferdBlendMode, XrPreferdColorFormats, XrPreferdDepthFormats,
+};
 use input::XrInput;
 use openxr as xr;
-use passthrough::{start_passthrough, supports_passthrough};
+use passthrough::{
+    start_passthrough, supports_passthrough, update_passthrough_blend_mode, PassthroughEnabled,
+    PassthroughSettings,
+};
+use performance::{
+    apply_performance_levels, update_performance_state, SetXrPerformanceLevel,
+    XrPerformanceNotification, XrPerformanceState,
+};
 use resources::*;
 use xr::FormFactor;
-use xr_init::{xr_only, XrEnableStatus, XrRenderData};
+use xr_init::{
+    run_xr_before_submit, run_xr_frame_begin, run_xr_frame_end, update_xr_focus,
+    update_xr_session_state, xr_only, OnFocusLost, XrBeforeSubmit, XrEnableStatus, XrFocusChanged,
+    XrFocused, XrFrameBegin, XrFrameEnd, XrInstanceLost, XrRenderData, XrResolutionChanged,
+    XrSessionState, XrSessionStateChanged,
+};
+use xr_input::controller_models::XrInteractionProfileChanged;
 use xr_input::controllers::XrControllerType;
 use xr_input::hands::emulated::HandEmulationPlugin;
-use xr_input::hands::hand_tracking::{HandTrackingData, HandTrackingPlugin};
-use xr_input::OpenXrInput;
+use xr_input::hands::hand_tracking::{HandJointSet, HandTrackingData, HandTrackingPlugin};
+use xr_input::recenter::XrReferenceSpaceChanged;
+use xr_input::xr_camera::{
+    apply_clip_planes, apply_render_scale, scale_fov, warn_fov_scale, FovScale,
+};
+use xr_input::{OpenXrInput, QuatConv, Vec3Conv};
 
 const VIEW_TYPE: xr::ViewConfigurationType = xr::ViewConfigurationType::PRIMARY_STEREO;
 
 pub const LEFT_XR_TEXTURE_HANDLE: ManualTextureViewHandle = ManualTextureViewHandle(1208214591);
 pub const RIGHT_XR_TEXTURE_HANDLE: ManualTextureViewHandle = ManualTextureViewHandle(3383858418);
 
+/// the texture view of the view at `index`, the left and right eye for the first two
+pub fn xr_view_texture_handle(index: usize) -> ManualTextureViewHandle {
+    match index {
+        0 => LEFT_XR_TEXTURE_HANDLE,
+        1 => RIGHT_XR_TEXTURE_HANDLE,
+        _ => ManualTextureViewHandle(RIGHT_XR_TEXTURE_HANDLE.0 + index as u32 - 1),
+    }
+}
+
 /// Adds OpenXR support to an App
 #[derive(Default)]
 pub struct OpenXrPlugin {
     reqeusted_extensions: XrExtensions,
     prefered_blend_mode: XrPreferdBlendMode,
+    prefered_depth_formats: XrPreferdDepthFormats,
+    prefered_color_formats: XrPreferdColorFormats,
+    api_layers: EnabledApiLayers,
     app_info: XrAppInfo,
 }
 
+impl OpenXrPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// the extensions to enable if the runtime has them, a warning is logged for optional
+    /// ones it doesn't have, see [`XrExtensions`] for the defaults
+    pub fn with_extensions(mut self, extensions: XrExtensions) -> Self {
+        self.reqeusted_extensions = extensions;
+        self
+    }
+    /// falls back to opaque with a warning if the runtime doesn't support it
+    pub fn with_blend_mode(mut self, blend_mode: XrPreferdBlendMode) -> Self {
+        self.prefered_blend_mode = blend_mode;
+        self
+    }
+    pub fn with_depth_formats(mut self, depth_formats: XrPreferdDepthFormats) -> Self {
+        self.prefered_depth_formats = depth_formats;
+        self
+    }
+    pub fn with_color_formats(mut self, color_formats: XrPreferdColorFormats) -> Self {
+        self.prefered_color_formats = color_formats;
+        self
+    }
+    pub fn with_api_layers(mut self, api_layers: EnabledApiLayers) -> Self {
+        self.api_layers = api_layers;
+        self
+    }
+    pub fn with_app_info(mut self, app_info: XrAppInfo) -> Self {
+        self.app_info = app_info;
+        self
+    }
+}
+
 #[derive(Resource)]
 pub struct FutureXrResources(
     pub  Arc<
@@ -81,6 +160,9 @@ impl Plugin for OpenXrPlugin {
             primary_window.clone(),
             self.reqeusted_extensions.clone(),
             self.prefered_blend_mode,
+            self.prefered_depth_formats.clone(),
+            self.prefered_color_formats.clone(),
+            self.api_layers.clone(),
             self.app_info.clone(),
         ) {
             Ok((
@@ -100,11 +182,15 @@ impl Plugin for OpenXrPlugin {
                 input,
                 views,
                 frame_state,
+                api_layers,
             )) => {
                 // std::thread::sleep(Duration::from_secs(5));
                 debug!("Configured wgpu adapter Limits: {:#?}", device.limits());
                 debug!("Configured wgpu adapter Features: {:#?}", device.features());
                 app.insert_resource(xr_instance.clone());
+                let enabled_extensions = EnabledXrExtensions::from_instance(&xr_instance);
+                info!("enabled optional xr exts: {:?}", enabled_extensions);
+                app.insert_resource(enabled_extensions);
                 app.insert_resource(session.clone());
                 app.insert_resource(blend_mode.clone());
                 app.insert_resource(resolution.clone());
@@ -115,6 +201,7 @@ impl Plugin for OpenXrPlugin {
                 app.insert_resource(input.clone());
                 app.insert_resource(views.clone());
                 app.insert_resource(frame_state.clone());
+                app.insert_resource(api_layers);
                 let xr_data = XrRenderData {
                     xr_instance,
                     xr_session: session,
@@ -139,6 +226,22 @@ impl Plugin for OpenXrPlugin {
                         RenderInstance(Arc::new(instance)),
                     ),
                 });
+                app.add_plugins((
+                    ExtractResourcePlugin::<XrEnvironmentBlendMode>::default(),
+                    ExtractResourcePlugin::<PassthroughEnabled>::default(),
+                    ExtractResourcePlugin::<XrInput>::default(),
+                    ExtractResourcePlugin::<XrLayerOrder>::default(),
+                    ExtractResourcePlugin::<XrRenderScale>::default(),
+                    ExtractResourcePlugin::<XrResolution>::default(),
+                    ExtractResourcePlugin::<XrFrameTiming>::default(),
+                    ExtractResourcePlugin::<XrShouldRender>::default(),
+                    ExtractResourcePlugin::<XrClipPlanes>::default(),
+                ));
+                app.init_resource::<XrLayerOrder>();
+                app.init_resource::<XrRenderScale>();
+                app.init_resource::<XrFrameTiming>();
+                app.init_resource::<XrShouldRender>();
+                app.init_resource::<XrClipPlanes>();
                 app.insert_resource(XrEnableStatus::Enabled);
             }
             Err(err) => {
@@ -175,7 +278,19 @@ impl Plugin for OpenXrPlugin {
                     )
                     .is_ok_and(|v| v);
             if hands {
-                app.insert_resource(HandTrackingData::new(&data.xr_session).unwrap());
+                let joint_set = app
+                    .world
+                    .get_resource::<HandJointSet>()
+                    .copied()
+                    .unwrap_or_default();
+                app.insert_resource(
+                    HandTrackingData::with_joint_set(
+                        &data.xr_instance,
+                        &data.xr_session,
+                        joint_set,
+                    )
+                    .unwrap(),
+                );
             } else {
                 app.insert_resource(DisableHandTracking::Both);
             }
@@ -188,28 +303,102 @@ impl Plugin for OpenXrPlugin {
                 )
                 .is_ok_and(|v| v);
 
-            let (left, right) = data.xr_swapchain.get_render_views();
-            let left = ManualTextureView {
-                texture_view: left.into(),
-                size: *data.xr_resolution,
-                format: *data.xr_format,
-            };
-            let right = ManualTextureView {
-                texture_view: right.into(),
-                size: *data.xr_resolution,
-                format: *data.xr_format,
-            };
+            let render_views = data.xr_swapchain.get_render_views();
             app.add_systems(PreUpdate, xr_begin_frame.run_if(xr_only()));
+            let frames_in_flight = XrFramesInFlight::new(0.into());
+            app.insert_resource(frames_in_flight.clone());
+            app.init_resource::<MaxFrameLatency>();
+            //bevy's Msaa is left alone unless XrMsaaSetting is inserted
+            app.add_systems(PreUpdate, apply_xr_msaa.run_if(xr_only()));
+            app.add_systems(
+                PreUpdate,
+                limit_frames_in_flight
+                    .run_if(xr_only())
+                    .before(xr_begin_frame),
+            );
+            app.add_schedule(Schedule::new(XrFrameBegin));
+            app.add_systems(
+                PreUpdate,
+                run_xr_frame_begin.run_if(xr_only()).after(xr_begin_frame),
+            );
+            app.init_resource::<XrFocused>();
+            app.init_resource::<OnFocusLost>();
+            app.add_event::<XrFocusChanged>();
+            app.add_event::<XrInstanceLost>();
+            app.init_resource::<XrSessionState>();
+            app.add_event::<XrSessionStateChanged>();
+            app.add_systems(
+                PreUpdate,
+                (update_xr_focus, update_xr_session_state)
+                    .run_if(xr_only())
+                    .after(xr_begin_frame),
+            );
+            app.add_systems(
+                PreUpdate,
+                update_frame_timing.run_if(xr_only()).after(xr_begin_frame),
+            );
+            app.add_event::<XrResolutionChanged>();
+            app.add_systems(
+                PreUpdate,
+                recreate_swapchain_on_resolution_change
+                    .run_if(xr_only())
+                    .after(xr_begin_frame),
+            );
+            app.init_resource::<XrPerformanceState>();
+            app.add_event::<XrPerformanceNotification>();
+            app.add_event::<SetXrPerformanceLevel>();
+            app.add_event::<XrInteractionProfileChanged>();
+            app.add_event::<XrReferenceSpaceChanged>();
+            app.init_resource::<FovScale>();
+            app.add_systems(PreUpdate, warn_fov_scale.run_if(xr_only()));
+            app.add_systems(PostUpdate, apply_render_scale.run_if(xr_only()));
+            app.add_systems(
+                PostUpdate,
+                apply_clip_planes
+                    .run_if(xr_only())
+                    .before(CameraUpdateSystem),
+            );
+            app.add_systems(
+                PreUpdate,
+                (update_performance_state, apply_performance_levels)
+                    .run_if(xr_only())
+                    .after(xr_begin_frame),
+            );
+            app.init_resource::<XrFoveationLevel>();
+            app.add_systems(PreUpdate, apply_foveation_level.run_if(xr_only()));
+            app.insert_resource(PassthroughEnabled(passthrough));
+            app.init_resource::<PassthroughSettings>();
+            app.add_systems(PreUpdate, update_passthrough_blend_mode.run_if(xr_only()));
+            let quad_layers = XrQuadLayers::new(Mutex::default());
+            app.insert_resource(quad_layers.clone());
+            app.add_systems(
+                PostUpdate,
+                sync_quad_layers
+                    .run_if(xr_only())
+                    .after(TransformSystem::TransformPropagate),
+            );
             let mut manual_texture_views = app.world.resource_mut::<ManualTextureViews>();
-            manual_texture_views.insert(LEFT_XR_TEXTURE_HANDLE, left);
-            manual_texture_views.insert(RIGHT_XR_TEXTURE_HANDLE, right);
+            for (index, view) in render_views.into_iter().enumerate() {
+                let view = ManualTextureView {
+                    texture_view: view,
+                    size: *data.xr_resolution,
+                    format: *data.xr_format,
+                };
+                manual_texture_views.insert(xr_view_texture_handle(index), view);
+            }
             drop(manual_texture_views);
+            let passthrough_layer = passthrough.then(|| {
+                info!("Passthrough!");
+                let (pl, _p) = start_passthrough(&data);
+                // the main world only checks that it exists, the render world submits it
+                app.insert_resource(pl.clone());
+                pl
+            });
             let render_app = app.sub_app_mut(RenderApp);
 
-            if passthrough {
-                info!("Passthrough!");
-                let (pl, p) = start_passthrough(&data);
+            if let Some(pl) = passthrough_layer {
                 render_app.insert_resource(pl);
+                render_app.insert_resource(PassthroughEnabled(true));
                 info!("Inserted XrPassthroughLayer resource!");
                 // app.insert_resource(p);
                 // if !app.world.contains_resource::<ClearColor>() {
@@ -228,15 +417,28 @@ impl Plugin for OpenXrPlugin {
             render_app.insert_resource(data.xr_input.clone());
             render_app.insert_resource(data.xr_views.clone());
             render_app.insert_resource(data.xr_frame_state.clone());
+            render_app.insert_resource(frames_in_flight);
+            render_app.insert_resource(quad_layers);
             render_app.insert_resource(XrEnableStatus::Enabled);
+            render_app.add_schedule(Schedule::new(XrBeforeSubmit));
+            render_app.add_schedule(Schedule::new(XrFrameEnd));
+            render_app.add_systems(XrBeforeSubmit, copy_depth_to_swapchain);
             render_app.add_systems(
                 Render,
                 (
-                    post_frame
+                    (post_frame, acquire_quad_layer_images)
                         .run_if(xr_only())
                         .before(render_system)
                         .after(RenderSet::ExtractCommands),
-                    end_frame.run_if(xr_only()).after(render_system),
+                    (
+                        run_xr_before_submit,
+                        end_frame,
+                        count_frame_in_flight,
+                        run_xr_frame_end,
+                    )
+                        .chain()
+                        .run_if(xr_only())
+                        .after(render_system),
                 ),
             );
         }
@@ -247,9 +449,26 @@ impl Plugin for OpenXrPlugin {
 pub struct DefaultXrPlugins {
     pub reqeusted_extensions: XrExtensions,
     pub prefered_blend_mode: XrPreferdBlendMode,
+    pub prefered_depth_formats: XrPreferdDepthFormats,
+    pub prefered_color_formats: XrPreferdColorFormats,
+    pub api_layers: EnabledApiLayers,
     pub app_info: XrAppInfo,
 }
 
+impl DefaultXrPlugins {
+    /// see [`OpenXrPlugin::with_extensions`]
+    pub fn with_extensions(mut self, extensions: XrExtensions) -> Self {
+        self.reqeusted_extensions = extensions;
+        self
+    }
+    /// the blend mode to request at session creation, e.g. `Additive` for see through glasses,
+    /// falls back to opaque with a warning if the runtime doesn't support it
+    pub fn with_blend_mode(mut self, blend_mode: XrPreferdBlendMode) -> Self {
+        self.prefered_blend_mode = blend_mode;
+        self
+    }
+}
+
 impl PluginGroup for DefaultXrPlugins {
     fn build(self) -> PluginGroupBuilder {
         DefaultPlugins
@@ -259,6 +478,9 @@ impl PluginGroup for DefaultXrPlugins {
             .add_before::<RenderPlugin, _>(OpenXrPlugin {
                 prefered_blend_mode: self.prefered_blend_mode,
                 reqeusted_extensions: self.reqeusted_extensions,
+                prefered_depth_formats: self.prefered_depth_formats,
+                prefered_color_formats: self.prefered_color_formats,
+                api_layers: self.api_layers,
                 app_info: self.app_info.clone(),
             })
             .add_after::<OpenXrPlugin, _>(OpenXrInput::new(XrControllerType::OculusTouch))
@@ -284,6 +506,19 @@ impl PluginGroup for DefaultXrPlugins {
     }
 }
 
+/// The events [`xr_begin_frame`] sends for the polled OpenXR events
+#[derive(SystemParam)]
+pub struct XrEventWriters<'w> {
+    app_exit: EventWriter<'w, AppExit>,
+    performance_notifications: EventWriter<'w, XrPerformanceNotification>,
+    focus_changed: EventWriter<'w, XrFocusChanged>,
+    session_state_changed: EventWriter<'w, XrSessionStateChanged>,
+    instance_lost: EventWriter<'w, XrInstanceLost>,
+    interaction_profile_changed: EventWriter<'w, XrInteractionProfileChanged>,
+    reference_space_changed: EventWriter<'w, XrReferenceSpaceChanged>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn xr_begin_frame(
     instance: Res<XrInstance>,
     session: Res<XrSession>,
@@ -293,8 +528,16 @@ pub fn xr_begin_frame(
     swapchain: Res<XrSwapchain>,
     views: Res<XrViews>,
     input: Res<XrInput>,
-    mut app_exit: EventWriter<AppExit>,
+    mut events: XrEventWriters,
+    fov_scale: Res<FovScale>,
+    mut focused: Local<bool>,
+    mut instance_lost: Local<bool>,
 ) {
+    if *instance_lost {
+        // a frame after XrInstanceLost, so the app got a whole frame to react to it
+        events.app_exit.send(AppExit);
+        return;
+    }
     {
         let _span = info_span!("xr_poll_events");
         while let Some(event) = instance.poll_event(&mut Default::default()).unwrap() {
@@ -304,6 +547,16 @@ pub fn xr_begin_frame(
                     // Session state change is where we can begin and end sessions, as well as
                     // find quit messages!
                     info!("entered XR state {:?}", e.state());
+                    events.session_state_changed.send(XrSessionStateChanged {
+                        state: e.state(),
+                        time: e.time(),
+                    });
+                    if (e.state() == xr::SessionState::FOCUSED) != *focused {
+                        *focused = e.state() == xr::SessionState::FOCUSED;
+                        events
+                            .focus_changed
+                            .send(XrFocusChanged { focused: *focused });
+                    }
                     match e.state() {
                         xr::SessionState::READY => {
                             session.begin(VIEW_TYPE).unwrap();
@@ -312,19 +565,55 @@ pub fn xr_begin_frame(
                         xr::SessionState::STOPPING => {
                             session.end().unwrap();
                             session_running.store(false, std::sync::atomic::Ordering::Relaxed);
-                            app_exit.send(AppExit);
+                            events.app_exit.send(AppExit);
                         }
                         xr::SessionState::EXITING | xr::SessionState::LOSS_PENDING => {
-                            app_exit.send(AppExit);
+                            events.app_exit.send(AppExit);
                             return;
                         }
                         _ => {}
                     }
                 }
-                InstanceLossPending(_) => return,
+                InstanceLossPending(e) => {
+                    warn!("XR instance loss pending, exiting");
+                    events.instance_lost.send(XrInstanceLost {
+                        loss_time: e.loss_time(),
+                    });
+                    session_running.store(false, std::sync::atomic::Ordering::Relaxed);
+                    *instance_lost = true;
+                    return;
+                }
                 EventsLost(e) => {
                     warn!("lost {} XR events", e.lost_event_count());
                 }
+                ReferenceSpaceChangePending(e) => {
+                    events
+                        .reference_space_changed
+                        .send(XrReferenceSpaceChanged {
+                            reference_space_type: e.reference_space_type(),
+                            change_time: e.change_time(),
+                            pose_in_previous_space: e.pose_valid().then(|| {
+                                let pose = e.pose_in_previous_space();
+                                Transform::from_translation(pose.position.to_vec3())
+                                    .with_rotation(pose.orientation.to_quat())
+                            }),
+                        });
+                }
+                InteractionProfileChanged(_) => {
+                    events
+                        .interaction_profile_changed
+                        .send(XrInteractionProfileChanged);
+                }
+                PerfSettingsEXT(e) => {
+                    events
+                        .performance_notifications
+                        .send(XrPerformanceNotification {
+                            domain: e.domain(),
+                            sub_domain: e.sub_domain(),
+                            from: e.from_level(),
+                            to: e.to_level(),
+                        });
+                }
                 _ => {}
             }
         }
@@ -345,7 +634,7 @@ pub fn xr_begin_frame(
     }
     {
         let _span = info_span!("xr_locate_views").entered();
-        *views.lock().unwrap() = session
+        let mut located = session
             .locate_views(
                 VIEW_TYPE,
                 frame_state.lock().unwrap().predicted_display_time,
@@ -353,6 +642,31 @@ pub fn xr_begin_frame(
             )
             .unwrap()
             .1;
+        // the cameras and the submitted layer both read these, so they stay in sync
+        if fov_scale.clamped() < 1.0 {
+            for view in located.iter_mut() {
+                view.fov = scale_fov(view.fov, fov_scale.clamped());
+            }
+        }
+        *views.lock().unwrap() = located;
+    }
+}
+
+pub fn update_frame_timing(
+    frame_state: Res<XrFrameState>,
+    mut timing: ResMut<XrFrameTiming>,
+    mut should_render: ResMut<XrShouldRender>,
+) {
+    let frame_state = frame_state.lock().unwrap();
+    let new = XrFrameTiming {
+        predicted_display_time: frame_state.predicted_display_time,
+        predicted_display_period: frame_state.predicted_display_period,
+    };
+    if *timing != new {
+        *timing = new;
+    }
+    if should_render.0 != frame_state.should_render {
+        should_render.0 = frame_state.should_render;
     }
 }
 
@@ -372,22 +686,82 @@ pub fn post_frame(
     }
     {
         let _span = info_span!("xr_update_manual_texture_views").entered();
-        let (left, right) = swapchain.get_render_views();
-        let left = ManualTextureView {
-            texture_view: left.into(),
-            size: **resolution,
-            format: **format,
+        for (index, view) in swapchain.get_render_views().into_iter().enumerate() {
+            let view = ManualTextureView {
+                texture_view: view,
+                size: **resolution,
+                format: **format,
+            };
+            manual_texture_views.insert(xr_view_texture_handle(index), view);
+        }
+    }
+}
+
+/// copies the depth of every view into the depth swapchain, so the runtime can reproject with it
+pub fn copy_depth_to_swapchain(
+    swapchain: Res<XrSwapchain>,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+    clip_planes: Res<XrClipPlanes>,
+    views: Query<(&ExtractedCamera, &ViewDepthTexture)>,
+    mut warned: Local<bool>,
+) {
+    let Some(target) = swapchain.depth_texture() else {
+        return;
+    };
+    let _span = info_span!("xr_copy_depth").entered();
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("xr depth copy"),
+    });
+    for (camera, depth) in views.iter() {
+        let Some(NormalizedRenderTarget::TextureView(handle)) = camera.target else {
+            continue;
         };
-        let right = ManualTextureView {
-            texture_view: right.into(),
-            size: **resolution,
-            format: **format,
+        let Some(eye) = (0..swapchain.view_count()).find(|i| xr_view_texture_handle(*i) == handle)
+        else {
+            continue;
         };
-        manual_texture_views.insert(LEFT_XR_TEXTURE_HANDLE, left);
-        manual_texture_views.insert(RIGHT_XR_TEXTURE_HANDLE, right);
+        // copies need matching formats and sizes, msaa depth can't be copied at all
+        if depth.texture.format() != target.format()
+            || depth.texture.sample_count() != 1
+            || depth.texture.width() != target.width()
+            || depth.texture.height() != target.height()
+        {
+            if !*warned {
+                warn!("the camera depth doesn't match the depth swapchain, submitting color only");
+                *warned = true;
+            }
+            continue;
+        }
+        encoder.copy_texture_to_texture(
+            wgpu::ImageCopyTexture {
+                texture: &depth.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyTexture {
+                texture: &target,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: 0,
+                    y: 0,
+                    z: eye as u32,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d {
+                width: target.width(),
+                height: target.height(),
+                depth_or_array_layers: 1,
+            },
+        );
+        swapchain.set_depth_planes(*clip_planes);
     }
+    queue.submit([encoder.finish()]);
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn end_frame(
     xr_frame_state: Res<XrFrameState>,
     views: Res<XrViews>,
@@ -396,10 +770,23 @@ pub fn end_frame(
     resolution: Res<XrResolution>,
     environment_blend_mode: Res<XrEnvironmentBlendMode>,
     passthrough_layer: Option<Res<XrPassthroughLayer>>,
+    passthrough_enabled: Option<Res<PassthroughEnabled>>,
+    layer_order: Res<XrLayerOrder>,
+    render_scale: Res<XrRenderScale>,
+    quad_layers: Res<XrQuadLayers>,
+    should_render: Res<XrShouldRender>,
 ) {
+    let passthrough_layer = passthrough_layer.filter(|_| passthrough_enabled.is_some_and(|p| p.0));
+    let mut quad_layers = quad_layers.lock().unwrap();
     {
         let _span = info_span!("xr_release_image").entered();
         swapchain.release_image().unwrap();
+        for state in quad_layers.values_mut().filter(|s| s.acquired) {
+            if let Err(err) = state.swapchain.release_image() {
+                warn!("unable to release the image of a quad layer: {}", err);
+                state.acquired = false;
+            }
+        }
     }
     {
         let _span = info_span!("xr_end_frame").entered();
@@ -407,10 +794,20 @@ pub fn end_frame(
             xr_frame_state.lock().unwrap().predicted_display_time,
             &views.lock().unwrap(),
             &input.stage,
-            **resolution,
+            render_scale.scaled(**resolution),
             **environment_blend_mode,
             passthrough_layer.map(|p| p.into_inner()),
+            *layer_order,
+            &quad_layers
+                .values()
+                .filter(|s| s.acquired && s.visible)
+                .map(QuadLayerState::submit)
+                .collect::<Vec<_>>(),
+            should_render.0,
         );
+        for state in quad_layers.values_mut() {
+            state.acquired = false;
+        }
         match result {
             Ok(_) => {}
             Err(e) => warn!("error: {}", e),
@@ -418,6 +815,125 @@ pub fn end_frame(
     }
 }
 
+/// counts the frame as in flight until the gpu finished the work submitted for it
+pub fn count_frame_in_flight(frames_in_flight: Res<XrFramesInFlight>, queue: Res<RenderQueue>) {
+    frames_in_flight.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+    let frames_in_flight = frames_in_flight.clone();
+    queue.on_submitted_work_done(move || {
+        frames_in_flight.fetch_sub(1, std::sync::atomic::Ordering::AcqRel);
+    });
+}
+
+pub fn apply_xr_msaa(
+    setting: Option<Res<XrMsaaSetting>>,
+    swapchain: Res<XrSwapchain>,
+    mut msaa: ResMut<Msaa>,
+) {
+    let Some(setting) = setting else {
+        return;
+    };
+    if !setting.is_changed() {
+        return;
+    }
+    let max = swapchain.max_sample_count();
+    let new = setting.to_msaa(max);
+    if new.samples() != setting.0 {
+        warn!(
+            "XrMsaaSetting of {} is not possible, the runtime allows up to {}, using {}",
+            setting.0,
+            max,
+            new.samples()
+        );
+    }
+    *msaa = new;
+}
+
+/// recreates the swapchain when the runtime recommends another resolution. It only changes
+/// while the app is in the background, so this checks again whenever the session state changes
+#[allow(clippy::too_many_arguments)]
+pub fn recreate_swapchain_on_resolution_change(
+    instance: Res<XrInstance>,
+    swapchain: Res<XrSwapchain>,
+    device: Res<RenderDevice>,
+    format: Res<XrFormat>,
+    mut resolution: ResMut<XrResolution>,
+    mut manual_texture_views: ResMut<ManualTextureViews>,
+    mut state_changed: EventReader<XrSessionStateChanged>,
+    mut resolution_changed: EventWriter<XrResolutionChanged>,
+) {
+    if state_changed.read().last().is_none() {
+        return;
+    }
+    let views = instance
+        .system(FormFactor::HEAD_MOUNTED_DISPLAY)
+        .and_then(|system| instance.enumerate_view_configuration_views(system, VIEW_TYPE));
+    let recommended = match views.as_deref() {
+        Ok([view, ..]) => UVec2::new(
+            view.recommended_image_rect_width,
+            view.recommended_image_rect_height,
+        ),
+        Ok([]) => return,
+        Err(err) => {
+            warn!("unable to get the recommended resolution: {}", err);
+            return;
+        }
+    };
+    if recommended == swapchain.resolution() {
+        return;
+    }
+    info!(
+        "the recommended resolution changed to {}, recreating the swapchain",
+        recommended
+    );
+    // the render world only acquires an image while rendering, none is acquired here
+    if let Err(err) =
+        graphics::recreate_swapchain_images(&swapchain, device.wgpu_device(), **format, recommended)
+    {
+        warn!("unable to recreate the swapchain: {}", err);
+        return;
+    }
+    *resolution = recommended.into();
+    for (index, view) in swapchain.get_render_views().into_iter().enumerate() {
+        let view = ManualTextureView {
+            texture_view: view,
+            size: recommended,
+            format: **format,
+        };
+        manual_texture_views.insert(xr_view_texture_handle(index), view);
+    }
+    resolution_changed.send(XrResolutionChanged {
+        resolution: recommended,
+    });
+}
+
+/// blocks before waiting on the next frame until the gpu is at most `MaxFrameLatency` frames behind
+pub fn limit_frames_in_flight(
+    max_latency: Res<MaxFrameLatency>,
+    frames_in_flight: Res<XrFramesInFlight>,
+    swapchain: Res<XrSwapchain>,
+    device: Res<RenderDevice>,
+    mut effective: Local<Option<u32>>,
+) {
+    let max = max_latency
+        .0
+        .clamp(1, swapchain.image_count().max(1) as u32);
+    if *effective != Some(max) {
+        if max != max_latency.0 {
+            warn!(
+                "MaxFrameLatency of {} is not possible with {} swapchain images",
+                max_latency.0,
+                swapchain.image_count()
+            );
+        }
+        info!("using a max frame latency of {}", max);
+        *effective = Some(max);
+    }
+    if frames_in_flight.load(std::sync::atomic::Ordering::Acquire) >= max {
+        let _span = info_span!("xr_wait_gpu").entered();
+        device.wgpu_device().poll(wgpu::Maintain::Wait);
+    }
+}
+
 pub fn locate_views(
     views: Res<XrViews>,
     input: Res<XrInput>,
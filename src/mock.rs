@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+
+use crate::xr_input::controller_input::ControllerInput;
+use crate::xr_input::hands::emulated::{
+    update_hand_skeleton_from_emulated, EmulatedHandInputs, HandEmulationConfig, HandSplayConfig,
+    MissingControllerPolicy, ThumbRestPoses,
+};
+use crate::xr_input::smoothing::{ControllerSmoothing, CurlSmoothing};
+use crate::xr_input::trackers::{
+    update_open_xr_controllers, ControllerLocation, ControllerLocations, ControllerPoseOrigin,
+    TrackingLost,
+};
+use crate::xr_input::Hand;
+
+/// The controller state [`MockXrPlugin`] applies every frame, set it from tests or tools.
+/// Poses are relative to the tracking root like the real ones, `None` marks the controller
+/// as [`TrackingLost`] and keeps its last transform.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct MockXrInput {
+    pub left: Option<Transform>,
+    pub right: Option<Transform>,
+    pub input: ControllerInput,
+    /// what the emulated hands are posed from, the fingers are open by default
+    pub fingers: EmulatedHandInputs,
+}
+
+impl Default for MockXrInput {
+    fn default() -> Self {
+        Self {
+            left: Some(Transform::from_xyz(-0.2, 1.0, -0.3)),
+            right: Some(Transform::from_xyz(0.2, 1.0, -0.3)),
+            input: default(),
+            fingers: default(),
+        }
+    }
+}
+
+impl MockXrInput {
+    pub fn pose(&self, hand: Hand) -> Option<Transform> {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+
+    pub fn set_pose(&mut self, hand: Hand, pose: Option<Transform>) {
+        match hand {
+            Hand::Left => self.left = pose,
+            Hand::Right => self.right = pose,
+        }
+    }
+}
+
+/// Feeds [`MockXrInput`] into the inputs of the real controller and hand emulation systems
+/// instead of an OpenXR runtime, so input handling can run headless, e.g. with
+/// `MinimalPlugins` in tests. Use it instead of the xr plugins, the OpenXR actions and spaces
+/// themselves can't be faked without a runtime, only what the systems read from them.
+pub struct MockXrPlugin;
+
+impl Plugin for MockXrPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MockXrInput>();
+        app.init_resource::<ControllerInput>();
+        app.init_resource::<ControllerLocations>();
+        app.init_resource::<ControllerSmoothing>();
+        app.init_resource::<ControllerPoseOrigin>();
+        app.init_resource::<EmulatedHandInputs>();
+        app.init_resource::<ThumbRestPoses>();
+        app.init_resource::<HandEmulationConfig>();
+        app.init_resource::<HandSplayConfig>();
+        app.init_resource::<MissingControllerPolicy>();
+        app.init_resource::<CurlSmoothing>();
+        app.add_systems(PreUpdate, apply_mock_xr_input);
+        app.add_systems(
+            Update,
+            (
+                update_open_xr_controllers,
+                update_hand_skeleton_from_emulated,
+            )
+                .chain(),
+        );
+    }
+}
+
+pub fn apply_mock_xr_input(
+    mock: Res<MockXrInput>,
+    mut input: ResMut<ControllerInput>,
+    mut locations: ResMut<ControllerLocations>,
+    mut fingers: ResMut<EmulatedHandInputs>,
+) {
+    if *input != mock.input {
+        *input = mock.input;
+    }
+    for hand in [Hand::Left, Hand::Right] {
+        // no separate aim space, aim along the controller
+        locations.set(hand, mock.pose(hand).map(ControllerLocation::from_grip));
+    }
+    if *fingers != mock.fingers {
+        *fingers = mock.fingers;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_input::hands::common::HandBoneRadius;
+    use crate::xr_input::hands::emulated::{update_hand_bones_emulated, EmulatedHandPoseData};
+    use crate::xr_input::hands::{BoneTrackingStatus, HandBone};
+    use crate::xr_input::trackers::{AimPose, OpenXRLeftController, OpenXRRightController};
+
+    fn mock_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        app.add_plugins(MockXrPlugin);
+        app
+    }
+
+    #[test]
+    fn controllers_follow_the_mock() {
+        let mut app = mock_app();
+        let left = app
+            .world
+            .spawn((
+                Transform::default(),
+                OpenXRLeftController,
+                AimPose(Transform::default()),
+            ))
+            .id();
+        app.update();
+        let pose = MockXrInput::default().left.unwrap();
+        assert_eq!(*app.world.get::<Transform>(left).unwrap(), pose);
+        assert_eq!(app.world.get::<AimPose>(left).unwrap().0, pose);
+
+        app.world.resource_mut::<MockXrInput>().input.left.trigger = 1.0;
+        app.world
+            .resource_mut::<MockXrInput>()
+            .set_pose(Hand::Left, None);
+        app.update();
+        assert_eq!(app.world.resource::<ControllerInput>().left.trigger, 1.0);
+        assert!(app.world.get::<TrackingLost>(left).is_some());
+        assert_eq!(*app.world.get::<Transform>(left).unwrap(), pose);
+
+        let moved = Transform::from_xyz(-0.3, 1.1, -0.2);
+        app.world
+            .resource_mut::<MockXrInput>()
+            .set_pose(Hand::Left, Some(moved));
+        app.update();
+        assert!(app.world.get::<TrackingLost>(left).is_none());
+        assert_eq!(*app.world.get::<Transform>(left).unwrap(), moved);
+    }
+
+    #[test]
+    fn emulated_hands_follow_the_mock_fingers() {
+        let mut app = mock_app();
+        app.world
+            .spawn((Transform::default(), OpenXRRightController));
+        let tip = app
+            .world
+            .spawn((
+                Transform::default(),
+                HandBone::IndexTip,
+                Hand::Right,
+                BoneTrackingStatus::Emulated,
+                HandBoneRadius(0.0),
+            ))
+            .id();
+        app.world
+            .resource_mut::<MockXrInput>()
+            .fingers
+            .right
+            .index_curl = 1.0;
+        app.update();
+
+        let controller = MockXrInput::default().right.unwrap();
+        let pose = EmulatedHandPoseData {
+            index_curl: 1.0,
+            ..default()
+        };
+        let expected = update_hand_bones_emulated(
+            &controller,
+            Hand::Right,
+            &pose,
+            &HandEmulationConfig::default(),
+            &HandSplayConfig::default(),
+        )[HandBone::IndexTip.get_index_from_bone()];
+        let tip = app.world.get::<Transform>(tip).unwrap();
+        assert!(tip.translation.distance(expected.translation) < 1e-5);
+    }
+}
@@ -1,10 +1,13 @@
 use std::ptr::null;
 
+use bevy::prelude::*;
+use bevy::render::extract_resource::ExtractResource;
 use openxr::SystemId;
 
 use crate::{
-    resources::{XrInstance, XrPassthroughLayer},
+    resources::{XrEnvironmentBlendMode, XrInstance, XrPassthroughLayer},
     xr_init::XrRenderData,
+    VIEW_TYPE,
 };
 use openxr as xr;
 use xr::{
@@ -72,3 +75,75 @@ pub fn start_passthrough(render_data: &XrRenderData) -> (XrPassthroughLayer, Pas
 pub fn supports_passthrough(a: &XrInstance, b: SystemId) -> Result<bool, ()> {
     Ok(true)
 }
+
+/// Toggles the passthrough layer at runtime.
+/// Requires the `fb_passthrough` extension to be enabled, otherwise this does nothing.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq, ExtractResource)]
+pub struct PassthroughEnabled(pub bool);
+
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct PassthroughSettings {
+    /// when passthrough is toggled, also switch the environment blend mode
+    /// and clear to a transparent background, reverting both when it gets turned off
+    pub auto_blend_mode: bool,
+}
+
+impl Default for PassthroughSettings {
+    fn default() -> Self {
+        Self {
+            auto_blend_mode: true,
+        }
+    }
+}
+
+pub fn update_passthrough_blend_mode(
+    passthrough_enabled: Res<PassthroughEnabled>,
+    settings: Res<PassthroughSettings>,
+    instance: Res<XrInstance>,
+    passthrough_layer: Option<Res<XrPassthroughLayer>>,
+    mut blend_mode: ResMut<XrEnvironmentBlendMode>,
+    mut clear_color: ResMut<ClearColor>,
+    mut opaque_clear_color: Local<Option<Color>>,
+) {
+    if !passthrough_enabled.is_changed() && !settings.is_changed() {
+        return;
+    }
+    if passthrough_enabled.0 && instance.exts().fb_passthrough.is_none() {
+        warn!("Passthrough was enabled, but the fb_passthrough extension isn't available");
+        return;
+    }
+    // without a layer the blend mode would only show a transparent clear color
+    if passthrough_enabled.0 && passthrough_layer.is_none() {
+        warn!("Passthrough was enabled, but it wasn't started at setup so there is no passthrough layer");
+        return;
+    }
+    if !settings.auto_blend_mode {
+        return;
+    }
+    if passthrough_enabled.0 {
+        let supported = instance
+            .system(xr::FormFactor::HEAD_MOUNTED_DISPLAY)
+            .and_then(|system| instance.enumerate_environment_blend_modes(system, VIEW_TYPE))
+            .unwrap_or_default();
+        // the fb passthrough layer is composited below the projection layer,
+        // so opaque still works as long as the background is transparent
+        let mode = [
+            xr::EnvironmentBlendMode::ALPHA_BLEND,
+            xr::EnvironmentBlendMode::ADDITIVE,
+        ]
+        .into_iter()
+        .find(|mode| supported.contains(mode))
+        .unwrap_or(xr::EnvironmentBlendMode::OPAQUE);
+        info!("Passthrough enabled, using blend mode {:?}", mode);
+        *blend_mode = XrEnvironmentBlendMode::new(mode);
+        if opaque_clear_color.is_none() {
+            *opaque_clear_color = Some(clear_color.0);
+        }
+        clear_color.0 = Color::NONE;
+    } else {
+        *blend_mode = XrEnvironmentBlendMode::new(xr::EnvironmentBlendMode::OPAQUE);
+        if let Some(color) = opaque_clear_color.take() {
+            clear_color.0 = color;
+        }
+    }
+}
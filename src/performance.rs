@@ -0,0 +1,124 @@
+use bevy::{prelude::*, utils::HashMap};
+use openxr as xr;
+use xr::{
+    PerfSettingsDomainEXT, PerfSettingsLevelEXT, PerfSettingsNotificationLevelEXT,
+    PerfSettingsSubDomainEXT,
+};
+
+use crate::resources::{XrInstance, XrSession};
+
+/// Sent when the runtime reports a change of a performance notification level,
+/// e.g. when the device starts to throttle because it got too hot.
+/// Only sent if XR_EXT_performance_settings is enabled.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct XrPerformanceNotification {
+    pub domain: PerfSettingsDomainEXT,
+    pub sub_domain: PerfSettingsSubDomainEXT,
+    pub from: PerfSettingsNotificationLevelEXT,
+    pub to: PerfSettingsNotificationLevelEXT,
+}
+
+/// Send to ask the runtime for a cpu or gpu performance level
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SetXrPerformanceLevel {
+    pub domain: PerfSettingsDomainEXT,
+    pub level: PerfSettingsLevelEXT,
+}
+
+/// Requested performance levels and the last notification level of every sub domain
+#[derive(Resource, Clone, Debug, Default)]
+pub struct XrPerformanceState {
+    /// `None` until a level was set through [`SetXrPerformanceLevel`]
+    pub cpu_level: Option<PerfSettingsLevelEXT>,
+    pub gpu_level: Option<PerfSettingsLevelEXT>,
+    pub notifications: HashMap<
+        (PerfSettingsDomainEXT, PerfSettingsSubDomainEXT),
+        PerfSettingsNotificationLevelEXT,
+    >,
+}
+
+impl XrPerformanceState {
+    pub fn notification(
+        &self,
+        domain: PerfSettingsDomainEXT,
+        sub_domain: PerfSettingsSubDomainEXT,
+    ) -> PerfSettingsNotificationLevelEXT {
+        self.notifications
+            .get(&(domain, sub_domain))
+            .copied()
+            .unwrap_or(PerfSettingsNotificationLevelEXT::NORMAL)
+    }
+
+    /// whether any sub domain is at warning or worse, a good time to lower quality
+    pub fn is_throttling(&self) -> bool {
+        self.notifications
+            .values()
+            .any(|level| *level != PerfSettingsNotificationLevelEXT::NORMAL)
+    }
+}
+
+pub fn supports_performance_settings(instance: &XrInstance) -> bool {
+    instance.exts().ext_performance_settings.is_some()
+}
+
+/// asks the runtime for a performance level, does nothing without XR_EXT_performance_settings
+pub fn set_performance_level(
+    instance: &XrInstance,
+    session: &XrSession,
+    domain: PerfSettingsDomainEXT,
+    level: PerfSettingsLevelEXT,
+) -> xr::Result<()> {
+    let Some(perf_settings) = instance.exts().ext_performance_settings.as_ref() else {
+        return Ok(());
+    };
+    let result = unsafe {
+        (perf_settings.perf_settings_set_performance_level)(session.as_raw(), domain, level)
+    };
+    match result.into_raw() >= 0 {
+        true => Ok(()),
+        false => Err(result),
+    }
+}
+
+pub fn update_performance_state(
+    mut state: ResMut<XrPerformanceState>,
+    mut notifications: EventReader<XrPerformanceNotification>,
+) {
+    for notification in notifications.read() {
+        if notification.to != PerfSettingsNotificationLevelEXT::NORMAL {
+            warn!(
+                "XR performance of {:?} {:?} is {:?}",
+                notification.domain, notification.sub_domain, notification.to
+            );
+        }
+        state.notifications.insert(
+            (notification.domain, notification.sub_domain),
+            notification.to,
+        );
+    }
+}
+
+pub fn apply_performance_levels(
+    instance: Res<XrInstance>,
+    session: Res<XrSession>,
+    mut state: ResMut<XrPerformanceState>,
+    mut requests: EventReader<SetXrPerformanceLevel>,
+) {
+    if !supports_performance_settings(&instance) {
+        if !requests.is_empty() {
+            warn!("XR_EXT_performance_settings is not enabled, ignoring performance level");
+            requests.clear();
+        }
+        return;
+    }
+    for request in requests.read() {
+        match set_performance_level(&instance, &session, request.domain, request.level) {
+            Ok(_) => match request.domain {
+                PerfSettingsDomainEXT::CPU => state.cpu_level = Some(request.level),
+                PerfSettingsDomainEXT::GPU => state.gpu_level = Some(request.level),
+                _ => {}
+            },
+            Err(err) => warn!("unable to set XR performance level: {}", err),
+        }
+    }
+}
@@ -1,9 +1,12 @@
+use std::ops::Deref;
 use std::ptr;
-use std::sync::atomic::AtomicBool;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
 
 use crate::resource_macros::*;
 use bevy::prelude::*;
+use bevy::render::extract_resource::ExtractResource;
+use bevy::render::render_resource::TextureView;
 use openxr as xr;
 use xr::{CompositionLayerFlags, CompositionLayerBase};
 use xr::sys::CompositionLayerPassthroughFB;
@@ -19,19 +22,345 @@ xr_arc_resource_wrapper!(XrFrameWaiter, Mutex<xr::FrameWaiter>);
 xr_arc_resource_wrapper!(XrSwapchain, Swapchain);
 xr_arc_resource_wrapper!(XrFrameState, Mutex<xr::FrameState>);
 xr_arc_resource_wrapper!(XrViews, Mutex<Vec<xr::View>>);
+xr_arc_resource_wrapper!(XrFramesInFlight, AtomicU32);
+
+/// How many frames the gpu may still be working on when the next frame is waited on.
+/// 1 gives the lowest latency, 2 or more lets the cpu run ahead for smoother throughput
+/// when the gpu workload is heavy. Values above the swapchain image count have no effect,
+/// acquiring the image already blocks there, so the effective value is clamped to it.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxFrameLatency(pub u32);
+
+impl Default for MaxFrameLatency {
+    fn default() -> Self {
+        Self(2)
+    }
+}
+
+/// Samples per pixel of the eye cameras, 1 disables MSAA.
+/// Bevy renders into a multisampled texture and resolves it into the single sampled
+/// swapchain image before the frame is submitted, so this drives bevy's [`Msaa`].
+/// Values the runtime or bevy don't support are lowered to the next one that works.
+/// Not inserted by default, without it bevy's [`Msaa`] is used as is.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XrMsaaSetting(pub u32);
+
+impl XrMsaaSetting {
+    /// the highest sample count bevy supports that is at most this and `max`
+    pub fn to_msaa(self, max: u32) -> Msaa {
+        match self.0.min(max) {
+            8.. => Msaa::Sample8,
+            4..=7 => Msaa::Sample4,
+            2..=3 => Msaa::Sample2,
+            _ => Msaa::Off,
+        }
+    }
+}
+
+/// Submit order of the composition layers. Layers are submitted from the lowest to the
+/// highest order and later layers are blended over earlier ones, so backgrounds like passthrough
+/// need a lower order than the projection and overlays a higher one.
+/// Layers with the same order keep the order they were added in.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XrLayerOrder {
+    pub passthrough: i32,
+    pub projection: i32,
+}
+
+impl XrLayerOrder {
+    pub const BACKGROUND: i32 = -100;
+    pub const SCENE: i32 = 0;
+    pub const OVERLAY: i32 = 100;
+
+    /// passthrough drawn over the scene, e.g. to punch windows into it with a masked layer
+    pub fn passthrough_over() -> Self {
+        Self {
+            passthrough: Self::OVERLAY,
+            ..default()
+        }
+    }
+
+    pub fn passthrough_under(&self) -> bool {
+        self.passthrough < self.projection
+    }
+}
+
+impl Default for XrLayerOrder {
+    fn default() -> Self {
+        Self {
+            passthrough: Self::BACKGROUND,
+            projection: Self::SCENE,
+        }
+    }
+}
+
+impl ExtractResource for XrLayerOrder {
+    type Source = Self;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        *source
+    }
+}
+
+/// Fraction of [`XrResolution`] that is rendered, per axis. Lowering it renders into a
+/// smaller region of the same swapchain image which the runtime upscales when compositing,
+/// so resolution can be traded for framerate at runtime without recreating the swapchain.
+/// Clamped to `MIN_RENDER_SCALE..=1.0`.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct XrRenderScale(pub f32);
+
+impl Default for XrRenderScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+pub const MIN_RENDER_SCALE: f32 = 0.25;
+
+impl XrRenderScale {
+    pub fn clamped(&self) -> f32 {
+        self.0.clamp(MIN_RENDER_SCALE, 1.0)
+    }
+
+    /// the size of the rendered region of each eye
+    pub fn scaled(&self, resolution: UVec2) -> UVec2 {
+        (resolution.as_vec2() * self.clamped())
+            .round()
+            .as_uvec2()
+            .max(UVec2::ONE)
+    }
+}
+
+// the rendered region has to match what the frame was rendered with
+impl ExtractResource for XrRenderScale {
+    type Source = Self;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        *source
+    }
+}
+
+/// Near and far plane of the eye cameras, in meters. Also submitted with the depth of the
+/// projection layer, so the runtime reprojects with the same planes.
+/// A far plane of `f32::INFINITY` renders with an infinite projection instead.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct XrClipPlanes {
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for XrClipPlanes {
+    fn default() -> Self {
+        Self {
+            near: 0.05,
+            far: 1000.0,
+        }
+    }
+}
+
+impl ExtractResource for XrClipPlanes {
+    type Source = Self;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        *source
+    }
+}
+
+/// Timing of the frame that is simulated, updated in `PreUpdate` once the frame was waited on.
+/// `predicted_display_period` is the refresh interval of the headset, stepping the simulation
+/// with it instead of bevy's `Time` keeps it at the cadence of the compositor.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XrFrameTiming {
+    pub predicted_display_time: xr::Time,
+    pub predicted_display_period: xr::Duration,
+}
+
+impl XrFrameTiming {
+    pub fn display_period_seconds(&self) -> f32 {
+        self.predicted_display_period.as_nanos() as f32 / 1e9
+    }
+}
+
+impl Default for XrFrameTiming {
+    fn default() -> Self {
+        Self {
+            predicted_display_time: xr::Time::from_nanos(1),
+            predicted_display_period: xr::Duration::from_nanos(1),
+        }
+    }
+}
+
+impl ExtractResource for XrFrameTiming {
+    type Source = Self;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        *source
+    }
+}
+
+/// Whether the runtime wants the current frame rendered, `false` e.g. while the headset is off
+/// the head. Frames that aren't rendered are submitted without layers, expensive work that only
+/// matters for the image can be skipped as well.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XrShouldRender(pub bool);
+
+impl Default for XrShouldRender {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+impl ExtractResource for XrShouldRender {
+    type Source = Self;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        *source
+    }
+}
+
+pub(crate) fn to_posef(transform: Transform) -> xr::Posef {
+    let (t, r) = (transform.translation, transform.rotation);
+    xr::Posef {
+        orientation: xr::Quaternionf {
+            x: r.x,
+            y: r.y,
+            z: r.z,
+            w: r.w,
+        },
+        position: xr::Vector3f {
+            x: t.x,
+            y: t.y,
+            z: t.z,
+        },
+    }
+}
+
+/// stable, so layers with the same order stay in the order they were added in
+pub(crate) fn sort_layers<T>(layers: &mut [(i32, T)]) {
+    layers.sort_by_key(|(order, _)| *order);
+}
+
+// the swapchain images are recreated at runtime when the recommended resolution changes
+impl ExtractResource for XrResolution {
+    type Source = Self;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        source.clone()
+    }
+}
+
+// the blend mode can change at runtime (e.g. when toggling passthrough)
+impl ExtractResource for XrEnvironmentBlendMode {
+    type Source = Self;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        source.clone()
+    }
+}
 
 pub enum Swapchain {
     Vulkan(SwapchainInner<xr::Vulkan>),
 }
 
+// escape hatch for calling extension functions this crate doesn't wrap.
+// XrInstance and XrSession deref to the openxr handles already.
+// whatever you do with these, don't end the session and don't destroy any handle,
+// the crate still owns them and will use them on the next frame
 impl Swapchain {
+    /// Raw handle of the swapchain that gets rendered to.
+    ///
+    /// # Safety
+    /// The handle must not be destroyed and no image may be acquired or released on it,
+    /// that would break the frame loop.
+    pub unsafe fn as_raw(&self) -> xr::sys::Swapchain {
+        match self {
+            Swapchain::Vulkan(swapchain) => swapchain
+                .images
+                .read()
+                .unwrap()
+                .handle
+                .lock()
+                .unwrap()
+                .as_raw(),
+        }
+    }
+
+    /// Runs `f` with the typed vulkan swapchain, `None` if the session doesn't use vulkan.
+    ///
+    /// # Safety
+    /// Same as [`Swapchain::as_raw`], the swapchain is locked while `f` runs,
+    /// so `f` must not wait on the render world.
+    pub unsafe fn with_vulkan<R>(
+        &self,
+        f: impl FnOnce(&xr::Swapchain<xr::Vulkan>) -> R,
+    ) -> Option<R> {
+        match self {
+            Swapchain::Vulkan(swapchain) => {
+                Some(f(&swapchain.images.read().unwrap().handle.lock().unwrap()))
+            }
+        }
+    }
+
     pub(crate) fn begin(&self) -> xr::Result<()> {
         match self {
             Swapchain::Vulkan(swapchain) => swapchain.begin(),
         }
     }
 
-    pub(crate) fn get_render_views(&self) -> (wgpu::TextureView, wgpu::TextureView) {
+    pub fn depth_format(&self) -> Option<wgpu::TextureFormat> {
+        match self {
+            Swapchain::Vulkan(swapchain) => swapchain.depth_format,
+        }
+    }
+
+    /// The depth image of the current frame, both eyes as array layers.
+    /// `None` without `XR_KHR_composition_layer_depth` or a depth format,
+    /// then only color is submitted.
+    pub fn depth_texture(&self) -> Option<wgpu::Texture> {
+        match self {
+            Swapchain::Vulkan(swapchain) => swapchain.depth_texture(),
+        }
+    }
+
+    /// sets the planes the submitted depth was rendered with
+    pub(crate) fn set_depth_planes(&self, planes: XrClipPlanes) {
+        match self {
+            Swapchain::Vulkan(swapchain) => {
+                if let Some(depth) = swapchain.images.read().unwrap().depth.as_ref() {
+                    *depth.planes.lock().unwrap() = planes;
+                }
+            }
+        }
+    }
+
+    /// the most samples per pixel the runtime allows for the eye views
+    pub fn max_sample_count(&self) -> u32 {
+        match self {
+            Swapchain::Vulkan(swapchain) => swapchain.max_sample_count,
+        }
+    }
+
+    pub fn image_count(&self) -> usize {
+        match self {
+            Swapchain::Vulkan(swapchain) => swapchain.images.read().unwrap().buffers.len(),
+        }
+    }
+
+    /// the resolution of the images, changes when they are recreated
+    pub fn resolution(&self) -> UVec2 {
+        match self {
+            Swapchain::Vulkan(swapchain) => swapchain.images.read().unwrap().resolution,
+        }
+    }
+
+    /// the number of views, one array layer of the swapchain each
+    pub fn view_count(&self) -> usize {
+        match self {
+            Swapchain::Vulkan(swapchain) => swapchain.view_count as usize,
+        }
+    }
+
+    pub(crate) fn get_render_views(&self) -> Vec<TextureView> {
         match self {
             Swapchain::Vulkan(swapchain) => swapchain.get_render_views(),
         }
@@ -55,6 +384,7 @@ impl Swapchain {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn end(
         &self,
         predicted_display_time: xr::Time,
@@ -63,25 +393,206 @@ impl Swapchain {
         resolution: UVec2,
         environment_blend_mode: xr::EnvironmentBlendMode,
         passthrough_layer: Option<&XrPassthroughLayer>,
+        layer_order: XrLayerOrder,
+        quad_layers: &[QuadLayerSubmit],
+        should_render: bool,
     ) -> xr::Result<()> {
         match self {
-            Swapchain::Vulkan(swapchain) => swapchain.end(
-                predicted_display_time,
-                views,
-                stage,
-                resolution,
-                environment_blend_mode,
-                passthrough_layer,
-            ),
+            Swapchain::Vulkan(swapchain) => {
+                let quad_layers: Vec<_> = quad_layers
+                    .iter()
+                    .map(|quad| {
+                        let XrLayerSwapchain::Vulkan(layer_swapchain) = quad.swapchain;
+                        (layer_swapchain, quad)
+                    })
+                    .collect();
+                swapchain.end(
+                    predicted_display_time,
+                    views,
+                    stage,
+                    resolution,
+                    environment_blend_mode,
+                    passthrough_layer,
+                    layer_order,
+                    &quad_layers,
+                    should_render,
+                )
+            }
         }
     }
 }
 
+static LIVE_SWAPCHAIN_TEXTURES: AtomicUsize = AtomicUsize::new(0);
+
+/// How many wgpu textures wrapping swapchain images are alive. They are freed together with
+/// their swapchain, so recreating swapchains shouldn't make this grow.
+pub fn live_swapchain_textures() -> usize {
+    LIVE_SWAPCHAIN_TEXTURES.load(Ordering::Relaxed)
+}
+
+/// The wgpu textures of the images of one swapchain, counted in [`live_swapchain_textures`]
+pub struct SwapchainTextures<T = wgpu::Texture>(Vec<T>);
+
+impl<T> FromIterator<T> for SwapchainTextures<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let textures: Vec<T> = iter.into_iter().collect();
+        LIVE_SWAPCHAIN_TEXTURES.fetch_add(textures.len(), Ordering::Relaxed);
+        Self(textures)
+    }
+}
+
+impl<T> Deref for SwapchainTextures<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> Drop for SwapchainTextures<T> {
+    fn drop(&mut self) {
+        LIVE_SWAPCHAIN_TEXTURES.fetch_sub(self.0.len(), Ordering::Relaxed);
+    }
+}
+
+// the field order is load-bearing: fields drop in declaration order, the wgpu textures wrap
+// the images of the swapchains without owning them and have to go before the swapchains
+// destroy those images
 pub struct SwapchainInner<G: xr::Graphics> {
+    /// replaced as a whole when the resolution changes, never while an image is acquired
+    pub(crate) images: RwLock<SwapchainImages<G>>,
     pub(crate) stream: Mutex<xr::FrameStream<G>>,
+    /// the typed session, extra layer swapchains are created on it
+    pub(crate) session: xr::Session<G>,
+    /// negotiated from `XrPreferdDepthFormats`, `None` if the runtime has no depth formats
+    pub(crate) depth_format: Option<wgpu::TextureFormat>,
+    pub(crate) max_sample_count: u32,
+    pub(crate) view_count: u32,
+}
+
+/// The images of the projection layer at one resolution
+// the field order is load-bearing, the views and textures drop before `depth` and `handle`
+pub struct SwapchainImages<G: xr::Graphics> {
+    /// a view per array layer of every image, `buffers` never changes after creation
+    pub(crate) render_views: Vec<Vec<TextureView>>,
+    pub(crate) buffers: SwapchainTextures,
+    /// only created with `XR_KHR_composition_layer_depth`
+    pub(crate) depth: Option<DepthSwapchain<G>>,
+    pub(crate) handle: Mutex<xr::Swapchain<G>>,
+    pub(crate) image_index: Mutex<usize>,
+    pub(crate) resolution: UVec2,
+}
+
+// a frame can only be submitted with a sub image for each located view,
+// e.g. a mono runtime or a transient frame with a single view is skipped instead
+pub(crate) fn views_match_swapchain(located: usize, view_count: u32) -> bool {
+    let matches = located == view_count as usize;
+    if !matches {
+        warn!(
+            "located {} views but the swapchain has {}, skipping the frame",
+            located, view_count
+        );
+    }
+    matches
+}
+
+/// the views of every array layer of `buffers`, created once instead of every frame
+pub(crate) fn create_render_views(
+    buffers: &[wgpu::Texture],
+    view_count: u32,
+) -> Vec<Vec<TextureView>> {
+    buffers
+        .iter()
+        .map(|texture| {
+            (0..view_count)
+                .map(|view| {
+                    texture
+                        .create_view(&wgpu::TextureViewDescriptor {
+                            dimension: Some(wgpu::TextureViewDimension::D2),
+                            array_layer_count: Some(1),
+                            base_array_layer: view,
+                            ..Default::default()
+                        })
+                        .into()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// the field order is load-bearing, `buffers` has to drop before `handle`
+pub struct DepthSwapchain<G: xr::Graphics> {
+    pub(crate) buffers: SwapchainTextures,
     pub(crate) handle: Mutex<xr::Swapchain<G>>,
-    pub(crate) buffers: Vec<wgpu::Texture>,
     pub(crate) image_index: Mutex<usize>,
+    pub(crate) planes: Mutex<XrClipPlanes>,
+}
+
+/// The swapchain of a composition layer besides the projection, e.g. a quad layer
+// the field order is load-bearing, `buffers` has to drop before `handle`
+pub struct LayerSwapchain<G: xr::Graphics> {
+    pub(crate) buffers: SwapchainTextures,
+    pub(crate) handle: Mutex<xr::Swapchain<G>>,
+    pub(crate) image_index: Mutex<usize>,
+    pub(crate) resolution: UVec2,
+}
+
+impl<G: xr::Graphics> LayerSwapchain<G> {
+    fn acquire_image(&self) -> xr::Result<()> {
+        let mut handle = self.handle.lock().unwrap();
+        let image_index = handle.acquire_image()?;
+        *self.image_index.lock().unwrap() = image_index as _;
+        handle.wait_image(xr::Duration::INFINITE)
+    }
+
+    fn release_image(&self) -> xr::Result<()> {
+        self.handle.lock().unwrap().release_image()
+    }
+
+    fn texture_view(&self) -> wgpu::TextureView {
+        self.buffers[*self.image_index.lock().unwrap()].create_view(&Default::default())
+    }
+}
+
+pub enum XrLayerSwapchain {
+    Vulkan(LayerSwapchain<xr::Vulkan>),
+}
+
+impl XrLayerSwapchain {
+    /// acquires the next image and waits until it can be rendered to
+    pub(crate) fn acquire_image(&self) -> xr::Result<()> {
+        match self {
+            XrLayerSwapchain::Vulkan(swapchain) => swapchain.acquire_image(),
+        }
+    }
+
+    pub(crate) fn release_image(&self) -> xr::Result<()> {
+        match self {
+            XrLayerSwapchain::Vulkan(swapchain) => swapchain.release_image(),
+        }
+    }
+
+    /// a view of the current image
+    pub(crate) fn texture_view(&self) -> wgpu::TextureView {
+        match self {
+            XrLayerSwapchain::Vulkan(swapchain) => swapchain.texture_view(),
+        }
+    }
+
+    pub fn resolution(&self) -> UVec2 {
+        match self {
+            XrLayerSwapchain::Vulkan(swapchain) => swapchain.resolution,
+        }
+    }
+}
+
+/// a quad to submit this frame, its image has to be released already
+pub(crate) struct QuadLayerSubmit<'a> {
+    pub(crate) swapchain: &'a XrLayerSwapchain,
+    /// in the stage space
+    pub(crate) pose: Transform,
+    pub(crate) size: Vec2,
+    pub(crate) order: i32,
 }
 
 impl<G: xr::Graphics> SwapchainInner<G> {
@@ -89,41 +600,55 @@ impl<G: xr::Graphics> SwapchainInner<G> {
         self.stream.lock().unwrap().begin()
     }
 
-    fn get_render_views(&self) -> (wgpu::TextureView, wgpu::TextureView) {
-        let texture = &self.buffers[*self.image_index.lock().unwrap()];
+    fn get_render_views(&self) -> Vec<TextureView> {
+        let images = self.images.read().unwrap();
+        images.render_views[*images.image_index.lock().unwrap()].clone()
+    }
 
-        (
-            texture.create_view(&wgpu::TextureViewDescriptor {
-                dimension: Some(wgpu::TextureViewDimension::D2),
-                array_layer_count: Some(1),
-                ..Default::default()
-            }),
-            texture.create_view(&wgpu::TextureViewDescriptor {
-                dimension: Some(wgpu::TextureViewDimension::D2),
-                array_layer_count: Some(1),
-                base_array_layer: 1,
-                ..Default::default()
-            }),
-        )
+    fn depth_texture(&self) -> Option<wgpu::Texture> {
+        let images = self.images.read().unwrap();
+        let depth = images.depth.as_ref()?;
+        Some(depth.buffers[*depth.image_index.lock().unwrap()].clone())
     }
 
     fn acquire_image(&self) -> xr::Result<()> {
-        let image_index = self.handle.lock().unwrap().acquire_image()?;
-        *self.image_index.lock().unwrap() = image_index as _;
+        let images = self.images.read().unwrap();
+        let image_index = images.handle.lock().unwrap().acquire_image()?;
+        *images.image_index.lock().unwrap() = image_index as _;
+        if let Some(depth) = images.depth.as_ref() {
+            let image_index = depth.handle.lock().unwrap().acquire_image()?;
+            *depth.image_index.lock().unwrap() = image_index as _;
+        }
         Ok(())
     }
 
     fn wait_image(&self) -> xr::Result<()> {
-        self.handle
+        let images = self.images.read().unwrap();
+        images
+            .handle
             .lock()
             .unwrap()
-            .wait_image(xr::Duration::INFINITE)
+            .wait_image(xr::Duration::INFINITE)?;
+        if let Some(depth) = images.depth.as_ref() {
+            depth
+                .handle
+                .lock()
+                .unwrap()
+                .wait_image(xr::Duration::INFINITE)?;
+        }
+        Ok(())
     }
 
     fn release_image(&self) -> xr::Result<()> {
-        self.handle.lock().unwrap().release_image()
+        let images = self.images.read().unwrap();
+        images.handle.lock().unwrap().release_image()?;
+        if let Some(depth) = images.depth.as_ref() {
+            depth.handle.lock().unwrap().release_image()?;
+        }
+        Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn end(
         &self,
         predicted_display_time: xr::Time,
@@ -132,7 +657,17 @@ impl<G: xr::Graphics> SwapchainInner<G> {
         resolution: UVec2,
         environment_blend_mode: xr::EnvironmentBlendMode,
         passthrough_layer: Option<&XrPassthroughLayer>,
+        layer_order: XrLayerOrder,
+        quad_layers: &[(&LayerSwapchain<G>, &QuadLayerSubmit)],
+        should_render: bool,
     ) -> xr::Result<()> {
+        if !should_render {
+            return self.stream.lock().unwrap().end(
+                predicted_display_time,
+                environment_blend_mode,
+                &[],
+            );
+        }
         let rect = xr::Rect2Di {
             offset: xr::Offset2Di { x: 0, y: 0 },
             extent: xr::Extent2Di {
@@ -140,78 +675,149 @@ impl<G: xr::Graphics> SwapchainInner<G> {
                 height: resolution.y as _,
             },
         };
-        let swapchain = self.handle.lock().unwrap();
-        if views.len() == 0 {
-            warn!("views are len of 0");
-            return Ok(());
-        }
-        match passthrough_layer {
-            Some(pass) => {
-                info!("Rendering with pass through");
-                let passthrough_layer = xr::sys::CompositionLayerPassthroughFB {
-                    ty: CompositionLayerPassthroughFB::TYPE,
-                    next: ptr::null(),
-                    flags: CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA,
-                    space: xr::sys::Space::NULL,
-                    layer_handle: pass.0,
-                };
-                self.stream.lock().unwrap().end(
-                    predicted_display_time,
-                    environment_blend_mode,
-                    &[
-                        unsafe {
-                            &*(&passthrough_layer as *const _ as *const CompositionLayerBase<G>)
-                        },
-                        &xr::CompositionLayerProjection::new()
-                            .layer_flags(CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA)
-                            .space(stage)
-                            .views(&[
-                                xr::CompositionLayerProjectionView::new()
-                                    .pose(views[0].pose)
-                                    .fov(views[0].fov)
-                                    .sub_image(
-                                        xr::SwapchainSubImage::new()
-                                            .swapchain(&swapchain)
-                                            .image_array_index(0)
-                                            .image_rect(rect),
-                                    ),
-                                xr::CompositionLayerProjectionView::new()
-                                    .pose(views[1].pose)
-                                    .fov(views[1].fov)
-                                    .sub_image(
-                                        xr::SwapchainSubImage::new()
-                                            .swapchain(&swapchain)
-                                            .image_array_index(1)
-                                            .image_rect(rect),
-                                    ),
-                            ]),
-                    ],
-                )
-            }
-            None => self.stream.lock().unwrap().end(
+        let images = self.images.read().unwrap();
+        let swapchain = images.handle.lock().unwrap();
+        if !views_match_swapchain(views.len(), self.view_count) {
+            // the frame still has to end, without layers the runtime shows nothing for it
+            return self.stream.lock().unwrap().end(
                 predicted_display_time,
                 environment_blend_mode,
-                &[&xr::CompositionLayerProjection::new().space(stage).views(&[
-                    xr::CompositionLayerProjectionView::new()
-                        .pose(views[0].pose)
-                        .fov(views[0].fov)
-                        .sub_image(
-                            xr::SwapchainSubImage::new()
-                                .swapchain(&swapchain)
-                                .image_array_index(0)
-                                .image_rect(rect),
-                        ),
-                    xr::CompositionLayerProjectionView::new()
-                        .pose(views[1].pose)
-                        .fov(views[1].fov)
-                        .sub_image(
-                            xr::SwapchainSubImage::new()
-                                .swapchain(&swapchain)
-                                .image_array_index(1)
-                                .image_rect(rect),
-                        ),
-                ])],
-            ),
+                &[],
+            );
         }
+        let depth_swapchain = images.depth.as_ref().map(|d| d.handle.lock().unwrap());
+        let planes = images
+            .depth
+            .as_ref()
+            .map_or(XrClipPlanes::default(), |d| *d.planes.lock().unwrap());
+        let depth_infos = depth_swapchain.as_ref().map(|depth_swapchain| {
+            (0..self.view_count)
+                .map(|view| xr::sys::CompositionLayerDepthInfoKHR {
+                    ty: xr::sys::CompositionLayerDepthInfoKHR::TYPE,
+                    next: ptr::null(),
+                    sub_image: xr::SwapchainSubImage::new()
+                        .swapchain(depth_swapchain)
+                        .image_array_index(view)
+                        .image_rect(rect)
+                        .into_raw(),
+                    min_depth: 0.0,
+                    max_depth: 1.0,
+                    // bevy renders reverse z, a depth of 0 is at the far plane
+                    near_z: planes.far,
+                    far_z: planes.near,
+                })
+                .collect::<Vec<_>>()
+        });
+        let mut projection_views: Vec<_> = views
+            .iter()
+            .zip(0..)
+            .map(|(view, index)| {
+                xr::CompositionLayerProjectionView::new()
+                    .pose(view.pose)
+                    .fov(view.fov)
+                    .sub_image(
+                        xr::SwapchainSubImage::new()
+                            .swapchain(&swapchain)
+                            .image_array_index(index)
+                            .image_rect(rect),
+                    )
+            })
+            .collect();
+        if let Some(depth_infos) = depth_infos.as_ref() {
+            for (view, depth_info) in projection_views.iter_mut().zip(depth_infos) {
+                let mut raw = *view.as_raw();
+                raw.next = depth_info as *const _ as *const _;
+                // SAFETY: the depth info outlives the views, both live until the frame ended
+                *view = unsafe { xr::CompositionLayerProjectionView::from_raw(raw) };
+            }
+        }
+        let mut projection_layer = xr::CompositionLayerProjection::new()
+            .space(stage)
+            .views(&projection_views);
+        // the projection has to blend with whatever is drawn behind it
+        if passthrough_layer.is_some() && layer_order.passthrough_under() {
+            projection_layer =
+                projection_layer.layer_flags(CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA);
+        }
+        let passthrough_layer =
+            passthrough_layer.map(|pass| xr::sys::CompositionLayerPassthroughFB {
+                ty: CompositionLayerPassthroughFB::TYPE,
+                next: ptr::null(),
+                flags: CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA,
+                space: xr::sys::Space::NULL,
+                layer_handle: pass.0,
+            });
+
+        let mut layers: Vec<(i32, &CompositionLayerBase<G>)> =
+            vec![(layer_order.projection, &*projection_layer)];
+        if let Some(passthrough_layer) = passthrough_layer.as_ref() {
+            layers.push((layer_order.passthrough, unsafe {
+                &*(passthrough_layer as *const _ as *const CompositionLayerBase<G>)
+            }));
+        }
+        // the swapchains have to stay locked until the frame ended
+        let quad_swapchains: Vec<_> = quad_layers
+            .iter()
+            .map(|(swapchain, _)| swapchain.handle.lock().unwrap())
+            .collect();
+        let quads: Vec<_> = quad_layers
+            .iter()
+            .zip(quad_swapchains.iter())
+            .map(|((layer_swapchain, quad), quad_swapchain)| {
+                let res = layer_swapchain.resolution;
+                let quad_layer = xr::CompositionLayerQuad::new()
+                    .layer_flags(CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA)
+                    .space(stage)
+                    .eye_visibility(xr::EyeVisibility::BOTH)
+                    .sub_image(
+                        xr::SwapchainSubImage::new()
+                            .swapchain(quad_swapchain)
+                            .image_array_index(0)
+                            .image_rect(xr::Rect2Di {
+                                offset: xr::Offset2Di { x: 0, y: 0 },
+                                extent: xr::Extent2Di {
+                                    width: res.x as _,
+                                    height: res.y as _,
+                                },
+                            }),
+                    )
+                    .pose(to_posef(quad.pose))
+                    .size(xr::Extent2Df {
+                        width: quad.size.x,
+                        height: quad.size.y,
+                    });
+                (quad.order, quad_layer)
+            })
+            .collect();
+        layers.extend(quads.iter().map(|(order, quad)| (*order, &**quad)));
+        sort_layers(&mut layers);
+        let layers: Vec<&CompositionLayerBase<G>> = layers.into_iter().map(|(_, l)| l).collect();
+        self.stream
+            .lock()
+            .unwrap()
+            .end(predicted_display_time, environment_blend_mode, &layers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swapchain_textures_are_counted_until_dropped() {
+        let before = live_swapchain_textures();
+        let textures: SwapchainTextures<()> = std::iter::repeat(()).take(3).collect();
+        assert_eq!(textures.len(), 3);
+        assert_eq!(live_swapchain_textures(), before + 3);
+        drop(textures);
+        assert_eq!(live_swapchain_textures(), before);
+    }
+
+    #[test]
+    fn a_single_view_doesnt_match_a_stereo_swapchain() {
+        assert!(!views_match_swapchain(1, 2));
+        assert!(!views_match_swapchain(0, 2));
+        assert!(views_match_swapchain(1, 1));
+        assert!(views_match_swapchain(2, 2));
     }
 }
@@ -93,10 +93,147 @@ pub struct XrRenderUpdate;
 #[derive(Debug, ScheduleLabel, Clone, Copy, Hash, PartialEq, Eq)]
 pub struct XrPostRenderUpdate;
 
+/// Runs in the main world during `PreUpdate`, right after the frame has been waited on,
+/// begun and the views located. `XrFrameState` and `XrViews` already hold the values of the
+/// frame that is about to be simulated, so this is the place to update external tracking.
+#[derive(Debug, ScheduleLabel, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct XrFrameBegin;
+/// Runs in the render world after bevy rendered the frame, while the swapchain image
+/// is still acquired. Systems here may record extra GPU work into it, the frame is
+/// submitted to the runtime right after.
+#[derive(Debug, ScheduleLabel, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct XrBeforeSubmit;
+/// Runs in the render world after the frame was submitted to the runtime,
+/// the swapchain image must not be touched anymore.
+#[derive(Debug, ScheduleLabel, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct XrFrameEnd;
+
+/// What the input reports while the session is not focused, e.g. while the system menu is open
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnFocusLost {
+    /// stop updating input entirely, actions and tracked transforms keep their last value
+    PauseInput,
+    /// keep syncing, the runtime reports all actions as inactive and zeroed
+    ZeroInput,
+    /// keep the last action values without producing new presses, hand tracking keeps updating
+    #[default]
+    HoldLast,
+}
+
+/// Whether the session currently has input focus
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct XrFocused(pub bool);
+
+/// Sent when the session gains or loses input focus
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XrFocusChanged {
+    pub focused: bool,
+}
+
+/// The lifecycle state of the session as last reported by the runtime,
+/// e.g. `VISIBLE` while the system menu is shown over the app and `FOCUSED` again once it closed
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XrSessionState(pub openxr::SessionState);
+
+impl Default for XrSessionState {
+    fn default() -> Self {
+        Self(openxr::SessionState::UNKNOWN)
+    }
+}
+
+/// Sent for every session state the runtime reports, in order
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XrSessionStateChanged {
+    pub state: openxr::SessionState,
+    pub time: openxr::Time,
+}
+
+/// Sent after the swapchain was recreated because the runtime recommends another resolution,
+/// e.g. after it was changed in the runtime's settings. `XrResolution` already holds it.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XrResolutionChanged {
+    pub resolution: UVec2,
+}
+
+/// Sent when the runtime is about to lose the instance, e.g. because it is restarting.
+/// The render device is created from the instance, so it can't be recreated in place:
+/// the session is stopped and the app exits on the next frame, save anything that needs saving
+/// before `loss_time`. There is no `XrInstanceRecreated`, restarting is up to the app.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XrInstanceLost {
+    pub loss_time: openxr::Time,
+}
+
+pub fn update_xr_focus(
+    mut focused: ResMut<XrFocused>,
+    mut focus_changed: EventReader<XrFocusChanged>,
+) {
+    if let Some(event) = focus_changed.read().last() {
+        focused.0 = event.focused;
+    }
+}
+
+pub fn update_xr_session_state(
+    mut state: ResMut<XrSessionState>,
+    mut state_changed: EventReader<XrSessionStateChanged>,
+) {
+    if let Some(event) = state_changed.read().last() {
+        state.0 = event.state;
+    }
+}
+
+/// false while the session is unfocused and [`OnFocusLost::PauseInput`] is set
+pub fn xr_input_active(
+    focused: Option<Res<XrFocused>>,
+    on_focus_lost: Option<Res<OnFocusLost>>,
+) -> bool {
+    focused.map_or(true, |f| f.0) || on_focus_lost.map_or(true, |p| *p != OnFocusLost::PauseInput)
+}
+
+/// Whether gameplay can read the action states. False at startup and while unfocused,
+/// it only turns true once the actions have been synced with real state again.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct XrInputValid(pub bool);
+
+/// Sent when [`XrInputValid`] turns true, after startup, a restart or regaining focus
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct XrInputReady;
+
+/// How many successful action syncs are needed after regaining focus before the input is valid.
+/// Some runtimes still report stale or zeroed states on the first sync after resuming.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputResumeSyncs(pub u32);
+
+impl Default for InputResumeSyncs {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+/// run condition for systems that should skip frames with stale input
+pub fn xr_input_valid(valid: Option<Res<XrInputValid>>) -> bool {
+    valid.is_some_and(|v| v.0)
+}
+
+/// whether actions should be synced this frame
+pub(crate) fn should_sync_actions(focused: &XrFocused, on_focus_lost: OnFocusLost) -> bool {
+    focused.0 || on_focus_lost == OnFocusLost::ZeroInput
+}
+
 pub fn xr_only() -> impl FnMut(Option<Res<'_, XrEnableStatus>>) -> bool {
     resource_exists_and_equals(XrEnableStatus::Enabled)
 }
 
+/// like [`xr_only`], but also false while the session isn't `FOCUSED`,
+/// e.g. while the system overlay is up and poses are stale or invalid
+pub fn xr_focused(
+) -> impl FnMut(Option<Res<'_, XrEnableStatus>>, Option<Res<'_, XrSessionState>>) -> bool {
+    |status, state| {
+        status.is_some_and(|s| *s == XrEnableStatus::Enabled)
+            && state.is_some_and(|s| s.0 == openxr::SessionState::FOCUSED)
+    }
+}
+
 impl Plugin for RenderRestartPlugin {
     fn build(&self, app: &mut App) {
         add_schedules(app);
@@ -168,6 +305,16 @@ fn cleanup_oxr_session(xr_status: Option<Res<XrEnableStatus>>, session: Option<R
     }
 }
 
+pub fn run_xr_frame_begin(world: &mut World) {
+    world.run_schedule(XrFrameBegin);
+}
+pub fn run_xr_before_submit(world: &mut World) {
+    world.run_schedule(XrBeforeSubmit);
+}
+pub fn run_xr_frame_end(world: &mut World) {
+    world.run_schedule(XrFrameEnd);
+}
+
 pub fn update_xr_stuff(world: &mut World) {
     world.run_schedule(XrPreRenderUpdate);
     world.run_schedule(XrRenderUpdate);
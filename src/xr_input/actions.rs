@@ -9,7 +9,10 @@ use crate::{
     xr_init::XrPrePostSetup,
 };
 
-use super::oculus_touch::ActionSets;
+use super::{
+    oculus_touch::{subaction_path, ActionSets},
+    Hand,
+};
 
 pub use xr::sys::NULL_PATH;
 
@@ -29,14 +32,28 @@ fn create_action<T: xr::ActionTy>(
     action_name: &'static str,
     oxr_action_set: &xr::ActionSet,
     hands: &[xr::Path],
+    subaction_paths: &[xr::Path],
 ) -> xr::Action<T> {
-    match action.handednes {
-        ActionHandednes::Single => oxr_action_set
-            .create_action(action_name, &action.pretty_name, &[])
-            .unwrap_or_else(|_| panic!("Unable to create action: {}", action_name)),
-        ActionHandednes::Double => oxr_action_set
-            .create_action(action_name, &action.pretty_name, hands)
-            .unwrap_or_else(|_| panic!("Unable to create action: {}", action_name)),
+    oxr_action_set
+        .create_action(
+            action_name,
+            &action.pretty_name,
+            action_subaction_paths(action.handednes, hands, subaction_paths),
+        )
+        .unwrap_or_else(|_| panic!("Unable to create action: {}", action_name))
+}
+
+// `hands` are the left and right hand paths, `subaction_paths` the ones of a `Single` action
+fn action_subaction_paths<'a>(
+    handednes: ActionHandednes,
+    hands: &'a [xr::Path],
+    subaction_paths: &'a [xr::Path],
+) -> &'a [xr::Path] {
+    match handednes {
+        ActionHandednes::Single => subaction_paths,
+        ActionHandednes::Double => hands,
+        ActionHandednes::Left => &hands[..1],
+        ActionHandednes::Right => &hands[1..],
     }
 }
 pub fn setup_oxr_actions(world: &mut World) {
@@ -59,23 +76,30 @@ pub fn setup_oxr_actions(world: &mut World) {
         let oxr_action_set = instance
             .create_action_set(set_name, &set.pretty_name, set.priority)
             .expect("Unable to create action set");
+        let mut handednes: HashMap<&'static str, ActionHandednes> = default();
         for (action_name, action) in set.actions.into_iter() {
+            handednes.insert(action_name, action.handednes);
+            let paths: Vec<xr::Path> = action
+                .subaction_paths
+                .iter()
+                .map(|path| instance.string_to_path(path).unwrap())
+                .collect();
             use self::create_action as ca;
             let typed_action = match action.action_type {
                 ActionType::Vec2 => {
-                    TypedAction::Vec2(ca(&action, action_name, &oxr_action_set, &hands))
+                    TypedAction::Vec2(ca(&action, action_name, &oxr_action_set, &hands, &paths))
                 }
                 ActionType::F32 => {
-                    TypedAction::F32(ca(&action, action_name, &oxr_action_set, &hands))
+                    TypedAction::F32(ca(&action, action_name, &oxr_action_set, &hands, &paths))
                 }
                 ActionType::Bool => {
-                    TypedAction::Bool(ca(&action, action_name, &oxr_action_set, &hands))
+                    TypedAction::Bool(ca(&action, action_name, &oxr_action_set, &hands, &paths))
                 }
                 ActionType::PoseF => {
-                    TypedAction::PoseF(ca(&action, action_name, &oxr_action_set, &hands))
+                    TypedAction::PoseF(ca(&action, action_name, &oxr_action_set, &hands, &paths))
                 }
                 ActionType::Haptic => {
-                    TypedAction::Haptic(ca(&action, action_name, &oxr_action_set, &hands))
+                    TypedAction::Haptic(ca(&action, action_name, &oxr_action_set, &hands, &paths))
                 }
             };
             actions.insert(action_name, typed_action);
@@ -97,6 +121,7 @@ pub fn setup_oxr_actions(world: &mut World) {
             ActionSet {
                 // oxr_action_set,
                 actions,
+                handednes,
                 enabled: true,
             },
         );
@@ -112,10 +137,11 @@ pub fn setup_oxr_actions(world: &mut World) {
         })
         .zip([&action_bindings].into_iter().cycle())
         .flat_map(move |((set_name, action_name, action), bindings)| {
+            // actions without any binding just aren't suggested for a profile
             bindings
                 .get(&(set_name as &'static str, action_name as &'static str))
-                .unwrap()
-                .iter()
+                .into_iter()
+                .flatten()
                 .map(move |(dev, bindings)| (action, dev, bindings))
         })
         .map(|(action, dev, bindings)| {
@@ -137,9 +163,16 @@ pub fn setup_oxr_actions(world: &mut World) {
         b_indings.entry(dev).or_default().append(&mut bindings);
     }
     for (dev, bindings) in b_indings.into_iter() {
-        instance
+        // profiles from extensions the runtime doesn't support get rejected,
+        // that shouldn't stop the other profiles from working
+        if let Err(err) = instance
             .suggest_interaction_profile_bindings(instance.string_to_path(dev).unwrap(), &bindings)
-            .expect("Unable to suggest interaction bindings!");
+        {
+            warn!(
+                "Unable to suggest interaction bindings for {}: {}",
+                dev, err
+            );
+        }
     }
     session
         .attach_action_sets(&oxr_action_sets.iter().collect::<Vec<_>>())
@@ -149,9 +182,26 @@ pub fn setup_oxr_actions(world: &mut World) {
     world.insert_resource(action_sets);
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ActionHandednes {
     Single,
     Double,
+    /// only on `/user/hand/left`, its state has to be read with the left subaction path
+    Left,
+    /// only on `/user/hand/right`, like [`ActionHandednes::Left`]
+    Right,
+}
+
+impl ActionHandednes {
+    /// the hands the action has a subaction path for, none for `Single`
+    pub fn hands(&self) -> &'static [Hand] {
+        match self {
+            ActionHandednes::Single => &[],
+            ActionHandednes::Double => &[Hand::Left, Hand::Right],
+            ActionHandednes::Left => &[Hand::Left],
+            ActionHandednes::Right => &[Hand::Right],
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -171,10 +221,25 @@ pub enum TypedAction {
     Vec2(Action<Vector2f>),
 }
 
+impl TypedAction {
+    /// raw handle for extension functions that take an action,
+    /// it's owned by the action set so don't destroy it
+    pub fn as_raw(&self) -> xr::sys::Action {
+        match self {
+            TypedAction::F32(a) => a.as_raw(),
+            TypedAction::Bool(a) => a.as_raw(),
+            TypedAction::PoseF(a) => a.as_raw(),
+            TypedAction::Haptic(a) => a.as_raw(),
+            TypedAction::Vec2(a) => a.as_raw(),
+        }
+    }
+}
+
 pub struct SetupAction {
     pretty_name: String,
     action_type: ActionType,
     handednes: ActionHandednes,
+    subaction_paths: Vec<&'static str>,
     bindings: HashMap<&'static str, Vec<&'static str>>,
 }
 
@@ -198,6 +263,27 @@ impl SetupActionSet {
                 pretty_name,
                 action_type,
                 handednes,
+                subaction_paths: Vec::new(),
+                bindings: default(),
+            },
+        );
+    }
+    /// an action that isn't bound to the hands but to other top level paths,
+    /// e.g. `/user/vive_tracker_htcx/role/waist`, its state is read per path
+    pub fn new_action_with_subaction_paths(
+        &mut self,
+        name: &'static str,
+        pretty_name: String,
+        action_type: ActionType,
+        subaction_paths: &[&'static str],
+    ) {
+        self.actions.insert(
+            name,
+            SetupAction {
+                pretty_name,
+                action_type,
+                handednes: ActionHandednes::Single,
+                subaction_paths: subaction_paths.to_vec(),
                 bindings: default(),
             },
         );
@@ -215,6 +301,7 @@ impl SetupActionSet {
         }
     }
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct XrBinding {
     action: &'static str,
     path: &'static str,
@@ -257,6 +344,7 @@ pub struct ActionSet {
     // add functionality to enable/disable action sets
     enabled: bool,
     actions: HashMap<&'static str, TypedAction>,
+    handednes: HashMap<&'static str, ActionHandednes>,
 }
 
 #[derive(Resource)]
@@ -271,6 +359,7 @@ impl FmtDisplay for ActionError {
             ActionError::NoActionSet => "Action Set Not Found!",
             ActionError::NoAction => "Action Not Found!",
             ActionError::WrongActionType => "Wrong Action Type!",
+            ActionError::Runtime(err) => return write!(f, "Runtime Error: {}", err),
         };
         write!(f, "{}", err)
     }
@@ -281,9 +370,44 @@ pub enum ActionError {
     NoActionSet,
     NoAction,
     WrongActionType,
+    Runtime(xr::sys::Result),
 }
 
 impl XrActionSets {
+    /// every boolean action as (action set, action, handednes, action)
+    pub fn bool_actions(
+        &self,
+    ) -> impl Iterator<Item = (&'static str, &'static str, ActionHandednes, &Action<bool>)> {
+        self.sets.iter().flat_map(|(set_name, set)| {
+            set.actions
+                .iter()
+                .filter_map(move |(action_name, action)| match action {
+                    TypedAction::Bool(a) => Some((
+                        *set_name,
+                        *action_name,
+                        set.handednes
+                            .get(action_name)
+                            .copied()
+                            .unwrap_or(ActionHandednes::Single),
+                        a,
+                    )),
+                    _ => None,
+                })
+        })
+    }
+    /// the action without checking its type, e.g. to get at the raw handle
+    pub fn get_typed_action(
+        &self,
+        action_set: &'static str,
+        action_name: &'static str,
+    ) -> Result<&TypedAction, ActionError> {
+        self.sets
+            .get(action_set)
+            .ok_or(ActionError::NoActionSet)?
+            .actions
+            .get(action_name)
+            .ok_or(ActionError::NoAction)
+    }
     pub fn get_action_vec2(
         &self,
         action_set: &'static str,
@@ -369,4 +493,93 @@ impl XrActionSets {
             _ => Err(ActionError::WrongActionType),
         }
     }
+
+    /// vibrates the controller of `hand` through a haptic action, e.g. `haptic_feedback` of
+    /// `oculus_input`. `duration` is in seconds, `frequency` in hz and `amplitude` from 0.0 to 1.0,
+    /// a `frequency` of `None` lets the runtime pick
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_haptic_feedback(
+        &self,
+        session: &XrSession,
+        action_set: &'static str,
+        action_name: &'static str,
+        hand: Hand,
+        duration: f32,
+        frequency: Option<f32>,
+        amplitude: f32,
+    ) -> Result<(), ActionError> {
+        let vibration = xr::HapticVibration::new()
+            .amplitude(amplitude.clamp(0.0, 1.0))
+            .frequency(frequency.unwrap_or(xr::FREQUENCY_UNSPECIFIED))
+            .duration(xr::Duration::from_nanos((duration * 1e9) as i64));
+        self.get_action_haptic(action_set, action_name)?
+            .apply_feedback(session, subaction_path(hand), &vibration)
+            .map_err(ActionError::Runtime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // stand-ins for paths from the instance, only compared by value
+    fn paths() -> (xr::Path, xr::Path, xr::Path) {
+        (
+            xr::Path::from_raw(1),
+            xr::Path::from_raw(2),
+            xr::Path::from_raw(3),
+        )
+    }
+
+    #[test]
+    fn one_handed_actions_only_get_their_hand() {
+        let (left, right, _) = paths();
+        let hands = [left, right];
+        assert_eq!(
+            action_subaction_paths(ActionHandednes::Left, &hands, &[]),
+            &[left]
+        );
+        assert_eq!(
+            action_subaction_paths(ActionHandednes::Right, &hands, &[]),
+            &[right]
+        );
+        assert_eq!(
+            action_subaction_paths(ActionHandednes::Double, &hands, &[]),
+            &[left, right]
+        );
+    }
+
+    #[test]
+    fn single_actions_use_their_own_subaction_paths() {
+        let (left, right, device) = paths();
+        let hands = [left, right];
+        assert!(action_subaction_paths(ActionHandednes::Single, &hands, &[]).is_empty());
+        assert_eq!(
+            action_subaction_paths(ActionHandednes::Single, &hands, &[device]),
+            &[device]
+        );
+    }
+
+    #[test]
+    fn handedness_hands_match_the_subaction_paths() {
+        let (left, right, _) = paths();
+        let hands = [left, right];
+        for handednes in [
+            ActionHandednes::Single,
+            ActionHandednes::Double,
+            ActionHandednes::Left,
+            ActionHandednes::Right,
+        ] {
+            let subaction_paths = action_subaction_paths(handednes, &hands, &[]);
+            let expected: Vec<_> = handednes
+                .hands()
+                .iter()
+                .map(|hand| match hand {
+                    Hand::Left => left,
+                    Hand::Right => right,
+                })
+                .collect();
+            assert_eq!(subaction_paths, expected.as_slice());
+        }
+    }
 }
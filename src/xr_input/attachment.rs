@@ -0,0 +1,185 @@
+use bevy::prelude::{
+    Children, Commands, Component, Entity, GlobalTransform, Has, Query, Res, Resource, Transform,
+    With, Without,
+};
+
+use super::{
+    hands::HandBone,
+    trackers::{OpenXRController, OpenXRLeftController, OpenXRRightController},
+    Hand,
+};
+
+/// What part of the hand an [`AttachedToHand`] entity follows
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AttachPoint {
+    /// the `OpenXRLeftController`/`OpenXRRightController` entity
+    #[default]
+    Controller,
+    /// a hand bone entity spawned by `OpenXrHandInput`
+    Bone(HandBone),
+    /// the [`HandGripPoint`] of the hand, where a real hand would hold things
+    GripPoint,
+}
+
+/// Where a hand naturally holds things, in world space. Added to the palm bone entities
+/// and derived from the palm pose, so it works for tracked and emulated hands.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct HandGripPoint(pub Transform);
+
+/// Offset of the [`HandGripPoint`] from the palm bone, the default sits inside a closed fist.
+/// Bones point their -Z along the fingers and their -Y out of the palm.
+#[derive(Resource, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandGripPointOffset(pub Transform);
+
+impl Default for HandGripPointOffset {
+    fn default() -> Self {
+        Self(Transform::from_xyz(0.0, -0.035, -0.01))
+    }
+}
+
+/// Holds an entity in a hand without needing a physics engine.
+/// Every frame the entity's transform is set to the hand pose times `offset`,
+/// remove the component to let go of it again.
+/// The entity should not have a parent, its transform is written in world space.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct AttachedToHand {
+    pub hand: Hand,
+    pub offset: Transform,
+    pub point: AttachPoint,
+}
+
+impl AttachedToHand {
+    pub fn new(hand: Hand, offset: Transform) -> Self {
+        Self {
+            hand,
+            offset,
+            point: AttachPoint::Controller,
+        }
+    }
+
+    pub fn to_bone(hand: Hand, bone: HandBone, offset: Transform) -> Self {
+        Self {
+            hand,
+            offset,
+            point: AttachPoint::Bone(bone),
+        }
+    }
+
+    pub fn to_grip_point(hand: Hand, offset: Transform) -> Self {
+        Self {
+            hand,
+            offset,
+            point: AttachPoint::GripPoint,
+        }
+    }
+}
+
+/// runs after transform propagation, before [`update_attached_to_hand`]
+pub fn update_hand_grip_points(
+    mut commands: Commands,
+    offset: Res<HandGripPointOffset>,
+    mut palms: Query<(
+        Entity,
+        &GlobalTransform,
+        &HandBone,
+        Option<&mut HandGripPoint>,
+    )>,
+) {
+    for (entity, global, bone, grip_point) in palms.iter_mut() {
+        if *bone != HandBone::Palm {
+            continue;
+        }
+        let point = HandGripPoint(global.mul_transform(offset.0).compute_transform());
+        match grip_point {
+            Some(mut grip_point) => *grip_point = point,
+            None => {
+                commands.entity(entity).insert(point);
+            }
+        }
+    }
+}
+
+/// runs after transform propagation and writes both the local and global transform,
+/// so the attached entity is rendered at the current hand pose this frame.
+/// Its children are moved along by [`propagate_attached_to_hand`]
+pub fn update_attached_to_hand(
+    mut attached_query: Query<
+        (&AttachedToHand, &mut Transform, &mut GlobalTransform),
+        (Without<OpenXRController>, Without<HandBone>),
+    >,
+    controller_query: Query<
+        (
+            &GlobalTransform,
+            Has<OpenXRLeftController>,
+            Has<OpenXRRightController>,
+        ),
+        (With<OpenXRController>, Without<AttachedToHand>),
+    >,
+    bone_query: Query<
+        (&GlobalTransform, &Hand, &HandBone, Option<&HandGripPoint>),
+        Without<AttachedToHand>,
+    >,
+) {
+    for (attached, mut transform, mut global_transform) in attached_query.iter_mut() {
+        let target = match attached.point {
+            AttachPoint::Controller => controller_query
+                .iter()
+                .find(|(_, left, right)| match attached.hand {
+                    Hand::Left => *left,
+                    Hand::Right => *right,
+                })
+                .map(|(global, _, _)| *global),
+            AttachPoint::Bone(bone) => bone_query
+                .iter()
+                .find(|(_, hand, b, _)| **hand == attached.hand && **b == bone)
+                .map(|(global, _, _, _)| *global),
+            AttachPoint::GripPoint => bone_query
+                .iter()
+                .find(|(_, hand, _, grip_point)| **hand == attached.hand && grip_point.is_some())
+                .and_then(|(_, _, _, grip_point)| grip_point)
+                .map(|grip_point| GlobalTransform::from(grip_point.0)),
+        };
+        let Some(target) = target else {
+            continue;
+        };
+        let result = target.mul_transform(attached.offset);
+        *transform = result.compute_transform();
+        *global_transform = result;
+    }
+}
+
+/// runs after [`update_attached_to_hand`], the children of held entities were already
+/// propagated from the pose of the last frame and would lag behind it otherwise
+pub fn propagate_attached_to_hand(
+    attached_query: Query<(&GlobalTransform, &Children), With<AttachedToHand>>,
+    mut children_query: Query<
+        (&Transform, &mut GlobalTransform, Option<&Children>),
+        Without<AttachedToHand>,
+    >,
+) {
+    for (global, children) in attached_query.iter() {
+        propagate_children(global, children, &mut children_query);
+    }
+}
+
+fn propagate_children(
+    parent: &GlobalTransform,
+    children: &[Entity],
+    children_query: &mut Query<
+        (&Transform, &mut GlobalTransform, Option<&Children>),
+        Without<AttachedToHand>,
+    >,
+) {
+    for child in children {
+        let Ok((transform, mut global, grandchildren)) = children_query.get_mut(*child) else {
+            continue;
+        };
+        *global = parent.mul_transform(*transform);
+        let global = *global;
+        let Some(grandchildren) = grandchildren.map(|c| c.to_vec()) else {
+            continue;
+        };
+        propagate_children(&global, &grandchildren, children_query);
+    }
+}
@@ -0,0 +1,209 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::resources::XrSession;
+
+use super::{
+    actions::{ActionHandednes, XrActionSets},
+    oculus_touch::subaction_path,
+    Hand,
+};
+
+/// A boolean action of any attached action set
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ButtonId {
+    pub action_set: &'static str,
+    pub action: &'static str,
+}
+
+/// Sent whenever a button is pressed or released
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ControllerButtonChanged {
+    pub button: ButtonId,
+    /// `None` for actions that aren't split per hand
+    pub hand: Option<Hand>,
+    pub pressed: bool,
+}
+
+/// State of every boolean action, per hand for actions that are split per hand.
+/// Useful for rebinding and "press any button" flows.
+/// Like Bevy's `Input`, `just_pressed` and `just_released` only hold for the frame of the change.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ControllerButtons {
+    states: HashMap<(ButtonId, Option<Hand>), bool>,
+    just_pressed: HashSet<(ButtonId, Option<Hand>)>,
+    just_released: HashSet<(ButtonId, Option<Hand>)>,
+}
+
+impl ControllerButtons {
+    pub fn is_pressed(
+        &self,
+        action_set: &'static str,
+        action: &'static str,
+        hand: Option<Hand>,
+    ) -> bool {
+        self.states
+            .get(&(ButtonId { action_set, action }, hand))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn just_pressed(
+        &self,
+        action_set: &'static str,
+        action: &'static str,
+        hand: Option<Hand>,
+    ) -> bool {
+        self.just_pressed
+            .contains(&(ButtonId { action_set, action }, hand))
+    }
+
+    pub fn just_released(
+        &self,
+        action_set: &'static str,
+        action: &'static str,
+        hand: Option<Hand>,
+    ) -> bool {
+        self.just_released
+            .contains(&(ButtonId { action_set, action }, hand))
+    }
+
+    /// every pressed button, with the hand it is pressed on
+    pub fn pressed(&self) -> impl Iterator<Item = (ButtonId, Option<Hand>)> + '_ {
+        self.states
+            .iter()
+            .filter(|(_, pressed)| **pressed)
+            .map(|(key, _)| *key)
+    }
+
+    pub fn any_pressed(&self) -> bool {
+        self.states.values().any(|pressed| *pressed)
+    }
+
+    /// buttons that aren't split per hand are not counted
+    pub fn any_pressed_on(&self, hand: Hand) -> bool {
+        self.pressed().any(|(_, h)| h == Some(hand))
+    }
+}
+
+/// The current state of every boolean action, in the order of the action sets, written by
+/// [`read_controller_buttons`] for [`update_controller_buttons`] to find the changes
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ControllerButtonReadings(pub Vec<((ButtonId, Option<Hand>), bool)>);
+
+pub fn read_controller_buttons(
+    action_sets: Res<XrActionSets>,
+    session: Res<XrSession>,
+    mut readings: ResMut<ControllerButtonReadings>,
+) {
+    readings.0.clear();
+    for (action_set, action_name, handednes, action) in action_sets.bool_actions() {
+        let button = ButtonId {
+            action_set,
+            action: action_name,
+        };
+        let hands: Vec<Option<Hand>> = match handednes {
+            ActionHandednes::Single => vec![None],
+            _ => handednes.hands().iter().copied().map(Some).collect(),
+        };
+        for hand in hands.iter() {
+            let path = hand.map_or(openxr::Path::NULL, subaction_path);
+            let pressed = match action.state(&session, path) {
+                Ok(state) => state.is_active && state.current_state,
+                Err(_) => false,
+            };
+            readings.0.push(((button, *hand), pressed));
+        }
+    }
+}
+
+pub fn update_controller_buttons(
+    readings: Res<ControllerButtonReadings>,
+    mut buttons: ResMut<ControllerButtons>,
+    mut changed: EventWriter<ControllerButtonChanged>,
+) {
+    buttons.just_pressed.clear();
+    buttons.just_released.clear();
+    for &((button, hand), pressed) in readings.0.iter() {
+        let previous = buttons.states.insert((button, hand), pressed);
+        // only compared with the last state, the runtime's changed flag stays set while
+        // actions aren't synced, e.g. unfocused with `OnFocusLost::HoldLast`
+        if previous.unwrap_or_default() != pressed {
+            match pressed {
+                true => buttons.just_pressed.insert((button, hand)),
+                false => buttons.just_released.insert((button, hand)),
+            };
+            changed.send(ControllerButtonChanged {
+                button,
+                hand,
+                pressed,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIGGER: ButtonId = ButtonId {
+        action_set: "test",
+        action: "trigger",
+    };
+
+    fn buttons_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<ControllerButtonReadings>();
+        app.init_resource::<ControllerButtons>();
+        app.add_event::<ControllerButtonChanged>();
+        app.add_systems(Update, update_controller_buttons);
+        app
+    }
+
+    fn read(app: &mut App, pressed: bool) {
+        app.world.resource_mut::<ControllerButtonReadings>().0 =
+            vec![((TRIGGER, Some(Hand::Right)), pressed)];
+    }
+
+    #[test]
+    fn presses_only_hold_for_one_frame() {
+        let mut app = buttons_app();
+        read(&mut app, true);
+        app.update();
+        let buttons = app.world.resource::<ControllerButtons>();
+        assert!(buttons.just_pressed("test", "trigger", Some(Hand::Right)));
+        assert!(buttons.is_pressed("test", "trigger", Some(Hand::Right)));
+
+        // no sync in between, e.g. unfocused, the readings are the same
+        app.update();
+        let buttons = app.world.resource::<ControllerButtons>();
+        assert!(buttons.just_pressed.is_empty());
+        assert!(buttons.is_pressed("test", "trigger", Some(Hand::Right)));
+        assert_eq!(
+            app.world
+                .resource::<Events<ControllerButtonChanged>>()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn releases_are_detected() {
+        let mut app = buttons_app();
+        read(&mut app, true);
+        app.update();
+        read(&mut app, false);
+        app.update();
+        let buttons = app.world.resource::<ControllerButtons>();
+        assert!(buttons.just_released("test", "trigger", Some(Hand::Right)));
+        assert!(!buttons.any_pressed());
+        app.update();
+        assert!(app
+            .world
+            .resource::<ControllerButtons>()
+            .just_released
+            .is_empty());
+    }
+}
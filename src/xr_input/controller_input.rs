@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+
+use crate::resources::XrSession;
+
+use super::{actions::XrActionSets, oculus_touch::subaction_path, Hand};
+
+const OCULUS_INPUT: &str = "oculus_input";
+
+/// The analog and touch state of one controller
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HandInput {
+    pub thumbstick: Vec2,
+    pub thumbstick_click: bool,
+    pub thumbstick_touch: bool,
+    pub thumbrest_touch: bool,
+    pub trigger: f32,
+    pub trigger_touched: bool,
+    pub grip: f32,
+}
+
+/// Every input of the [`OculusController`](super::oculus_touch::OculusController) actions,
+/// read once per frame after the actions were synced. Inputs a controller doesn't have stay
+/// at their default. [`XrActionSets`] still gives access to the actions themselves.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ControllerInput {
+    pub left: HandInput,
+    pub right: HandInput,
+    pub a_button: bool,
+    pub b_button: bool,
+    pub x_button: bool,
+    pub y_button: bool,
+    pub menu_button: bool,
+}
+
+impl ControllerInput {
+    pub fn hand(&self, hand: Hand) -> &HandInput {
+        match hand {
+            Hand::Left => &self.left,
+            Hand::Right => &self.right,
+        }
+    }
+    fn hand_mut(&mut self, hand: Hand) -> &mut HandInput {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+
+    pub fn thumbstick(&self, hand: Hand) -> Vec2 {
+        self.hand(hand).thumbstick
+    }
+
+    pub fn trigger(&self, hand: Hand) -> f32 {
+        self.hand(hand).trigger
+    }
+
+    pub fn grip(&self, hand: Hand) -> f32 {
+        self.hand(hand).grip
+    }
+
+    pub fn button_a_pressed(&self) -> bool {
+        self.a_button
+    }
+
+    pub fn button_b_pressed(&self) -> bool {
+        self.b_button
+    }
+
+    pub fn button_x_pressed(&self) -> bool {
+        self.x_button
+    }
+
+    pub fn button_y_pressed(&self) -> bool {
+        self.y_button
+    }
+
+    pub fn menu_pressed(&self) -> bool {
+        self.menu_button
+    }
+}
+
+pub fn update_controller_input(
+    action_sets: Res<XrActionSets>,
+    session: Res<XrSession>,
+    mut input: ResMut<ControllerInput>,
+) {
+    let f32_state = |action: &'static str, path| {
+        action_sets
+            .get_action_f32(OCULUS_INPUT, action)
+            .ok()
+            .and_then(|a| a.state(&session, path).ok())
+            .filter(|s| s.is_active)
+            .map_or(0.0, |s| s.current_state)
+    };
+    let bool_state = |action: &'static str, path| {
+        action_sets
+            .get_action_bool(OCULUS_INPUT, action)
+            .ok()
+            .and_then(|a| a.state(&session, path).ok())
+            .is_some_and(|s| s.is_active && s.current_state)
+    };
+    let mut new_input = ControllerInput {
+        a_button: bool_state("a_button", openxr::Path::NULL),
+        b_button: bool_state("b_button", openxr::Path::NULL),
+        x_button: bool_state("x_button", openxr::Path::NULL),
+        y_button: bool_state("y_button", openxr::Path::NULL),
+        menu_button: bool_state("menu_button", openxr::Path::NULL),
+        ..default()
+    };
+    for hand in [Hand::Left, Hand::Right] {
+        let path = subaction_path(hand);
+        *new_input.hand_mut(hand) = HandInput {
+            thumbstick: Vec2::new(
+                f32_state("thumbstick_x", path),
+                f32_state("thumbstick_y", path),
+            ),
+            thumbstick_click: bool_state("thumbstick_click", path),
+            thumbstick_touch: bool_state("thumbstick_touch", path),
+            thumbrest_touch: bool_state("thumbrest_touch", path),
+            trigger: f32_state("trigger", path),
+            trigger_touched: bool_state("trigger_touched", path),
+            grip: f32_state("squeeze", path),
+        };
+    }
+    // only touch the resource when something changed, for change detection
+    if *input != new_input {
+        *input = new_input;
+    }
+}
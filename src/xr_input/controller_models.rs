@@ -0,0 +1,210 @@
+use bevy::{prelude::*, utils::HashMap};
+use openxr as xr;
+
+use crate::resources::{XrInstance, XrSession};
+
+use super::{
+    hands::{
+        custom_skeleton::CustomHandSkeleton, BoneTrackingStatus, HandBone,
+        HandTrackingSourceChanged, HandTrackingSources,
+    },
+    oculus_touch::subaction_path,
+    trackers::{OpenXRLeftController, OpenXRRightController},
+    Hand,
+};
+
+/// Sent by the runtime when the interaction profile of any hand changed,
+/// see [`ControllerProfileChanged`] for which hand and what profile
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct XrInteractionProfileChanged;
+
+/// Sent when the interaction profile of a hand changed, e.g. a different controller was picked up.
+/// `profile` is `None` when there is no controller, e.g. when switching to hand tracking.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct ControllerProfileChanged {
+    pub hand: Hand,
+    /// see [`Hand::user_path`]
+    pub user_path: &'static str,
+    pub profile: Option<String>,
+}
+
+/// The current interaction profile of each hand, e.g. `/interaction_profiles/oculus/touch_controller`
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CurrentInteractionProfiles {
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl CurrentInteractionProfiles {
+    pub fn get(&self, hand: Hand) -> Option<&str> {
+        match hand {
+            Hand::Left => self.left.as_deref(),
+            Hand::Right => self.right.as_deref(),
+        }
+    }
+    fn get_mut(&mut self, hand: Hand) -> &mut Option<String> {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+}
+
+/// Scenes to show on the controllers for each interaction profile.
+/// Profiles without a model don't show anything.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ControllerModels {
+    pub left: HashMap<String, Handle<Scene>>,
+    pub right: HashMap<String, Handle<Scene>>,
+}
+
+impl ControllerModels {
+    pub fn with(
+        mut self,
+        profile: impl Into<String>,
+        left: Handle<Scene>,
+        right: Handle<Scene>,
+    ) -> Self {
+        let profile = profile.into();
+        self.left.insert(profile.clone(), left);
+        self.right.insert(profile, right);
+        self
+    }
+
+    pub fn get(&self, hand: Hand, profile: &str) -> Option<&Handle<Scene>> {
+        match hand {
+            Hand::Left => self.left.get(profile),
+            Hand::Right => self.right.get(profile),
+        }
+    }
+}
+
+/// A controller model spawned from [`ControllerModels`], despawned when the profile changes
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ControllerModel {
+    pub hand: Hand,
+}
+
+fn current_profile(instance: &XrInstance, session: &XrSession, hand: Hand) -> Option<String> {
+    let path = session
+        .current_interaction_profile(subaction_path(hand))
+        .ok()?;
+    if path == xr::Path::NULL {
+        return None;
+    }
+    instance.path_to_string(path).ok()
+}
+
+pub fn update_interaction_profiles(
+    instance: Res<XrInstance>,
+    session: Res<XrSession>,
+    mut runtime_changed: EventReader<XrInteractionProfileChanged>,
+    mut profiles: ResMut<CurrentInteractionProfiles>,
+    mut changed: EventWriter<ControllerProfileChanged>,
+) {
+    if runtime_changed.read().count() == 0 {
+        return;
+    }
+    for hand in [Hand::Left, Hand::Right] {
+        let profile = current_profile(&instance, &session, hand);
+        let current = profiles.get_mut(hand);
+        if *current != profile {
+            info!(
+                "interaction profile of {:?} hand is now {:?}",
+                hand, profile
+            );
+            *current = profile.clone();
+            changed.send(ControllerProfileChanged {
+                hand,
+                user_path: hand.user_path(),
+                profile,
+            });
+        }
+    }
+}
+
+/// swaps the controller model when the profile changes, and shows the hand
+/// instead of the controller when there is no controller or the hand is tracked
+pub fn swap_controller_models(
+    mut commands: Commands,
+    models: Res<ControllerModels>,
+    profiles: Res<CurrentInteractionProfiles>,
+    sources: Option<Res<HandTrackingSources>>,
+    mut profile_changed: EventReader<ControllerProfileChanged>,
+    mut source_changed: EventReader<HandTrackingSourceChanged>,
+    spawned: Query<(Entity, &ControllerModel)>,
+    mut controllers: Query<
+        (Entity, &mut Visibility, Has<OpenXRLeftController>),
+        Or<(With<OpenXRLeftController>, With<OpenXRRightController>)>,
+    >,
+    mut hands: Query<
+        (&Hand, &mut Visibility),
+        (
+            Or<(With<HandBone>, With<CustomHandSkeleton>)>,
+            Without<OpenXRLeftController>,
+            Without<OpenXRRightController>,
+        ),
+    >,
+) {
+    let controller_hand = |is_left: bool| match is_left {
+        true => Hand::Left,
+        false => Hand::Right,
+    };
+    let mut visibility_changed = [false; 2];
+    for event in profile_changed.read() {
+        visibility_changed[event.hand as usize] = true;
+        for (entity, model) in spawned.iter() {
+            if model.hand == event.hand {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        let controller = controllers
+            .iter()
+            .find(|(_, _, is_left)| controller_hand(*is_left) == event.hand)
+            .map(|(entity, _, _)| entity);
+        let (Some(controller), Some(profile)) = (controller, event.profile.as_deref()) else {
+            continue;
+        };
+        let Some(scene) = models.get(event.hand, profile) else {
+            continue;
+        };
+        let model = commands
+            .spawn((
+                SceneBundle {
+                    scene: scene.clone(),
+                    ..default()
+                },
+                ControllerModel { hand: event.hand },
+            ))
+            .id();
+        commands.entity(controller).add_child(model);
+    }
+    for event in source_changed.read() {
+        visibility_changed[event.hand as usize] = true;
+    }
+    for hand in [Hand::Left, Hand::Right] {
+        if !visibility_changed[hand as usize] {
+            continue;
+        }
+        let hand_tracked = sources
+            .as_ref()
+            .is_some_and(|s| s.get(hand) == BoneTrackingStatus::Tracked);
+        let show_controller = profiles.get(hand).is_some() && !hand_tracked;
+        for (_, mut visibility, is_left) in controllers.iter_mut() {
+            if controller_hand(is_left) == hand {
+                *visibility = match show_controller {
+                    true => Visibility::Inherited,
+                    false => Visibility::Hidden,
+                };
+            }
+        }
+        for (h, mut visibility) in hands.iter_mut() {
+            if *h == hand {
+                *visibility = match show_controller {
+                    true => Visibility::Hidden,
+                    false => Visibility::Inherited,
+                };
+            }
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use bevy::ecs::schedule::IntoSystemConfigs;
 use bevy::log::{debug, info};
 use bevy::prelude::{
-    Color, Gizmos, GlobalTransform, Plugin, Quat, Query, Res, Transform, Update, Vec2, Vec3, With,
-    Without,
+    Color, Gizmos, GlobalTransform, Has, Plugin, Quat, Query, Res, Resource, Transform, Update,
+    Vec2, Vec3, With, Without,
 };
 
 use crate::xr_init::xr_only;
@@ -18,7 +18,7 @@ use crate::xr_input::{
 
 use super::{
     actions::XrActionSets,
-    trackers::{OpenXRLeftController, OpenXRRightController, OpenXRTrackingRoot},
+    trackers::{AimPose, OpenXRLeftController, OpenXRRightController, OpenXRTrackingRoot},
 };
 
 /// add debug renderer for controllers
@@ -31,6 +31,73 @@ impl Plugin for OpenXrDebugRenderer {
     }
 }
 
+/// add debug renderer for the aim pose of the controllers
+#[derive(Default)]
+pub struct AimRayDebugRenderer;
+
+impl Plugin for AimRayDebugRenderer {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<AimRayGizmoConfig>();
+        app.add_systems(
+            Update,
+            draw_aim_ray_gizmos
+                .run_if(xr_only())
+                .run_if(|config: Res<AimRayGizmoConfig>| config.enabled),
+        );
+    }
+}
+
+/// Settings for [`AimRayDebugRenderer`], toggle `enabled` to show or hide the rays
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct AimRayGizmoConfig {
+    pub enabled: bool,
+    pub length: f32,
+    /// radius of the circle drawn at the aim origin
+    pub origin_radius: f32,
+    pub left_color: Color,
+    pub right_color: Color,
+}
+
+impl Default for AimRayGizmoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            length: 1.0,
+            origin_radius: 0.01,
+            left_color: Color::CYAN,
+            right_color: Color::FUCHSIA,
+        }
+    }
+}
+
+pub fn draw_aim_ray_gizmos(
+    mut gizmos: Gizmos,
+    config: Res<AimRayGizmoConfig>,
+    tracking_root_query: Query<&Transform, With<OpenXRTrackingRoot>>,
+    aim_query: Query<(&AimPose, Has<OpenXRLeftController>), Without<OpenXRTrackingRoot>>,
+) {
+    // the aim pose is relative to the tracking root, like the controller transforms
+    let root = tracking_root_query
+        .get_single()
+        .copied()
+        .unwrap_or_default();
+    for (aim, is_left) in aim_query.iter() {
+        let color = match is_left {
+            true => config.left_color,
+            false => config.right_color,
+        };
+        let origin = root.transform_point(aim.0.translation);
+        let direction = root.rotation.mul_vec3(aim.0.forward());
+        gizmos.sphere(
+            origin,
+            root.rotation * aim.0.rotation,
+            config.origin_radius,
+            color,
+        );
+        gizmos.ray(origin, direction * config.length, color);
+    }
+}
+
 #[allow(clippy::too_many_arguments, clippy::complexity)]
 pub fn draw_gizmos(
     mut gizmos: Gizmos,
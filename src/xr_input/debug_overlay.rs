@@ -0,0 +1,194 @@
+use std::fmt::Write;
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::view::RenderLayers;
+
+use crate::composition_layers::XrQuadLayer;
+use crate::resources::XrFrameState;
+use crate::xr_init::{xr_only, XrFocused, XrSessionState};
+
+use super::{
+    buttons::{ButtonId, ControllerButtonChanged},
+    controller_models::CurrentInteractionProfiles,
+    hands::HandTrackingSources,
+    head_locked::HeadLockedLayer,
+    Hand,
+};
+
+/// add an in headset overlay with fps, frame timing, session state,
+/// interaction profiles and tracking state, off until [`XrDiagnosticsOverlay::enabled`] is set
+#[derive(Default)]
+pub struct XrDiagnosticsOverlayPlugin;
+
+impl Plugin for XrDiagnosticsOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.init_resource::<XrDiagnosticsOverlay>();
+        app.add_event::<ControllerButtonChanged>();
+        app.add_systems(
+            Update,
+            (
+                toggle_diagnostics_overlay,
+                spawn_diagnostics_overlay,
+                update_diagnostics_overlay,
+            )
+                .chain()
+                .run_if(xr_only()),
+        );
+    }
+}
+
+/// Settings of [`XrDiagnosticsOverlayPlugin`]
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct XrDiagnosticsOverlay {
+    pub enabled: bool,
+    /// pressing this button toggles `enabled`, e.g. the menu button
+    pub toggle_button: Option<ButtonId>,
+    /// the overlay is drawn on this render layer before being put on the quad
+    pub render_layer: u8,
+    pub distance: f32,
+}
+
+impl Default for XrDiagnosticsOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            toggle_button: None,
+            render_layer: 31,
+            distance: 1.0,
+        }
+    }
+}
+
+/// The head locked [`XrQuadLayer`] showing the overlay
+#[derive(Component)]
+pub struct XrDiagnosticsOverlayQuad;
+
+#[derive(Component)]
+pub struct XrDiagnosticsOverlayText;
+
+const OVERLAY_SIZE: (u32, u32) = (512, 256);
+
+fn toggle_diagnostics_overlay(
+    mut overlay: ResMut<XrDiagnosticsOverlay>,
+    mut buttons: EventReader<ControllerButtonChanged>,
+) {
+    let toggle = overlay.toggle_button;
+    for event in buttons.read() {
+        if Some(event.button) == toggle && event.pressed {
+            overlay.enabled = !overlay.enabled;
+        }
+    }
+}
+
+/// spawned the first time the overlay is enabled, so it costs nothing unless it is used
+fn spawn_diagnostics_overlay(
+    mut commands: Commands,
+    overlay: Res<XrDiagnosticsOverlay>,
+    quads: Query<(), With<XrDiagnosticsOverlayQuad>>,
+) {
+    if !overlay.enabled || !quads.is_empty() {
+        return;
+    }
+    let resolution = UVec2::new(OVERLAY_SIZE.0, OVERLAY_SIZE.1);
+    let aspect = OVERLAY_SIZE.0 as f32 / OVERLAY_SIZE.1 as f32;
+    // composited by the runtime, so the text stays sharp at any render resolution
+    let quad = XrQuadLayer::new(Vec2::new(0.4 * aspect, 0.4), resolution);
+    let layer = RenderLayers::layer(overlay.render_layer);
+
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: -1,
+                target: RenderTarget::TextureView(quad.texture_view),
+                ..default()
+            },
+            camera_2d: Camera2d {
+                clear_color: bevy::core_pipeline::clear_color::ClearColorConfig::Custom(
+                    Color::rgba(0.0, 0.0, 0.0, 0.6),
+                ),
+            },
+            ..default()
+        },
+        layer,
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            ..default()
+        },
+        layer,
+        XrDiagnosticsOverlayText,
+    ));
+    commands.spawn((
+        SpatialBundle::default(),
+        quad,
+        HeadLockedLayer {
+            distance: overlay.distance,
+            ..default()
+        },
+        XrDiagnosticsOverlayQuad,
+    ));
+}
+
+fn update_diagnostics_overlay(
+    overlay: Res<XrDiagnosticsOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+    frame_state: Res<XrFrameState>,
+    session_state: Option<Res<XrSessionState>>,
+    focused: Option<Res<XrFocused>>,
+    profiles: Option<Res<CurrentInteractionProfiles>>,
+    hand_sources: Option<Res<HandTrackingSources>>,
+    mut quads: Query<&mut Visibility, With<XrDiagnosticsOverlayQuad>>,
+    mut texts: Query<&mut Text, With<XrDiagnosticsOverlayText>>,
+) {
+    for mut visibility in quads.iter_mut() {
+        *visibility = match overlay.enabled {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        };
+    }
+    if !overlay.enabled {
+        return;
+    }
+    let fps = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|d| d.smoothed())
+        .unwrap_or_default();
+    let frame_state = *frame_state.lock().unwrap();
+    let display_period = frame_state.predicted_display_period.as_nanos() as f64 / 1_000_000.0;
+    let mut out = String::new();
+    let _ = writeln!(out, "fps {:.1} display period {:.2}ms", fps, display_period);
+    let _ = writeln!(
+        out,
+        "session {:?} focused {} should render {}",
+        session_state.map_or(openxr::SessionState::UNKNOWN, |s| s.0),
+        focused.map_or(false, |f| f.0),
+        frame_state.should_render
+    );
+    for hand in [Hand::Left, Hand::Right] {
+        let profile = profiles
+            .as_ref()
+            .and_then(|p| p.get(hand).map(|p| p.to_string()))
+            .unwrap_or_else(|| "none".into());
+        let source = hand_sources
+            .as_ref()
+            .map(|s| format!("{:?}", s.get(hand)))
+            .unwrap_or_else(|| "-".into());
+        let _ = writeln!(out, "{:?} profile {} hand {}", hand, profile, source);
+    }
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = out.clone();
+    }
+}
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::XrInput,
+    resources::{XrFrameState, XrSession},
+};
+
+use super::{actions::XrActionSets, oculus_touch::OculusController, Hand};
+
+/// Turns the thumbsticks into directional presses for menu navigation.
+/// Off unless inserted, the raw axes stay available through [`OculusController::thumbstick`].
+#[derive(Resource, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct StickAsDpad {
+    /// the stick is ignored below this, the rest of the range is rescaled to 0.0 to 1.0
+    pub deadzone: f32,
+    /// rescaled deflection needed to press a direction
+    pub threshold: f32,
+    /// seconds a direction has to be held before it repeats
+    pub repeat_delay: f32,
+    /// repeats per second after `repeat_delay`, 0.0 disables repeating
+    pub repeat_rate: f32,
+}
+
+impl Default for StickAsDpad {
+    fn default() -> Self {
+        Self {
+            deadzone: 0.2,
+            threshold: 0.6,
+            repeat_delay: 0.5,
+            repeat_rate: 8.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DpadDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Sent when a stick is pushed in a direction, and again on every repeat while it is held
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DpadEvent {
+    pub hand: Hand,
+    pub direction: DpadDirection,
+    pub repeat: bool,
+}
+
+/// the direction an already deadzoned stick points in, the larger axis wins
+pub fn stick_direction(stick: Vec2, threshold: f32) -> Option<DpadDirection> {
+    if stick.length() < threshold {
+        return None;
+    }
+    Some(match stick.x.abs() > stick.y.abs() {
+        true if stick.x > 0.0 => DpadDirection::Right,
+        true => DpadDirection::Left,
+        false if stick.y > 0.0 => DpadDirection::Up,
+        false => DpadDirection::Down,
+    })
+}
+
+/// applies a radial deadzone, rescaling the rest of the range so it still reaches 1.0
+pub fn apply_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone || deadzone >= 1.0 {
+        return Vec2::ZERO;
+    }
+    let scaled = ((length - deadzone) / (1.0 - deadzone)).min(1.0);
+    stick / length * scaled
+}
+
+#[derive(Clone, Copy)]
+struct HeldDirection {
+    direction: DpadDirection,
+    // seconds until the next repeat
+    next_repeat: f32,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_stick_dpad(
+    config: Option<Res<StickAsDpad>>,
+    time: Res<Time>,
+    oculus_controller: Res<OculusController>,
+    frame_state: Res<XrFrameState>,
+    xr_input: Res<XrInput>,
+    session: Res<XrSession>,
+    action_sets: Res<XrActionSets>,
+    mut held: Local<[Option<HeldDirection>; 2]>,
+    mut events: EventWriter<DpadEvent>,
+) {
+    let Some(config) = config else {
+        return;
+    };
+    let frame_state = *frame_state.lock().unwrap();
+    let controller = oculus_controller.get_ref(&session, &frame_state, &xr_input, &action_sets);
+    for hand in [Hand::Left, Hand::Right] {
+        let stick = controller.thumbstick(hand);
+        let stick = apply_deadzone(Vec2::new(stick.x, stick.y), config.deadzone);
+        let held = &mut held[hand as usize];
+        let Some(direction) = stick_direction(stick, config.threshold) else {
+            *held = None;
+            continue;
+        };
+        match held {
+            Some(h) if h.direction == direction => {
+                if config.repeat_rate <= 0.0 {
+                    continue;
+                }
+                h.next_repeat -= time.delta_seconds();
+                if h.next_repeat <= 0.0 {
+                    h.next_repeat += 1.0 / config.repeat_rate;
+                    events.send(DpadEvent {
+                        hand,
+                        direction,
+                        repeat: true,
+                    });
+                }
+            }
+            _ => {
+                *held = Some(HeldDirection {
+                    direction,
+                    next_repeat: config.repeat_delay,
+                });
+                events.send(DpadEvent {
+                    hand,
+                    direction,
+                    repeat: false,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use openxr::{AnyGraphics, Posef, Session, Space, SpaceLocationFlags};
+
+use crate::{
+    input::XrInput,
+    resources::{XrFrameState, XrInstance, XrSession},
+};
+
+use super::{
+    actions::{ActionHandednes, ActionType, SetupActionSets, XrActionSets, XrBinding, NULL_PATH},
+    QuatConv, Vec3Conv,
+};
+
+pub const EYE_GAZE_ACTION_SET: &str = "eye_gaze";
+
+/// Whether eye gaze is available, needs
+/// [`XrExtensions::enable_eye_gaze`](crate::graphics::extensions::XrExtensions::enable_eye_gaze)
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EyeGazeSupported(pub bool);
+
+/// The combined gaze ray of both eyes relative to the tracking root, looking along -Z.
+/// `None` while the runtime doesn't track the eyes, e.g. before the user allowed it.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct GazePose(pub Option<Transform>);
+
+#[derive(Resource)]
+pub struct EyeGazeSpace(Space);
+
+pub(crate) fn setup_eye_gaze_action(
+    mut commands: Commands,
+    instance: Res<XrInstance>,
+    mut action_sets: ResMut<SetupActionSets>,
+) {
+    let supported = instance.exts().ext_eye_gaze_interaction.is_some();
+    commands.insert_resource(EyeGazeSupported(supported));
+    if !supported {
+        return;
+    }
+    let action_set = action_sets.add_action_set(EYE_GAZE_ACTION_SET, "Eye Gaze".into(), 0);
+    action_set.new_action(
+        "gaze_pose",
+        "Gaze Pose".into(),
+        ActionType::PoseF,
+        ActionHandednes::Single,
+    );
+    action_set.suggest_binding(
+        "/interaction_profiles/ext/eye_gaze_interaction",
+        &[XrBinding::new(
+            "gaze_pose",
+            "/user/eyes_ext/input/gaze_ext/pose",
+        )],
+    );
+}
+
+pub(crate) fn setup_eye_gaze_space(
+    mut commands: Commands,
+    action_sets: Res<XrActionSets>,
+    session: Res<XrSession>,
+) {
+    let Ok(action) = action_sets.get_action_posef(EYE_GAZE_ACTION_SET, "gaze_pose") else {
+        return;
+    };
+    let s = Session::<AnyGraphics>::clone(&session);
+    match action.create_space(s, NULL_PATH, Posef::IDENTITY) {
+        Ok(space) => commands.insert_resource(EyeGazeSpace(space)),
+        Err(err) => warn!("unable to create the eye gaze space: {}", err),
+    }
+}
+
+pub fn update_gaze_pose(
+    space: Option<Res<EyeGazeSpace>>,
+    frame_state: Res<XrFrameState>,
+    xr_input: Res<XrInput>,
+    mut gaze: ResMut<GazePose>,
+) {
+    let Some(space) = space else {
+        return;
+    };
+    let time = frame_state.lock().unwrap().predicted_display_time;
+    let valid = SpaceLocationFlags::POSITION_VALID | SpaceLocationFlags::ORIENTATION_VALID;
+    let pose = space
+        .0
+        .locate(&xr_input.stage, time)
+        .ok()
+        .filter(|l| l.location_flags.contains(valid))
+        .map(|l| {
+            Transform::from_translation(l.pose.position.to_vec3())
+                .with_rotation(l.pose.orientation.to_quat())
+        });
+    if gaze.0 != pose {
+        gaze.0 = pose;
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::{Event, EventWriter, Res, ResMut, Resource, Time, Vec3};
+use openxr::{SpaceVelocity, SpaceVelocityFlags};
+
+use crate::{
+    input::XrInput,
+    resources::{XrFrameState, XrSession},
+};
+
+use super::{actions::XrActionSets, oculus_touch::OculusController, Hand, Vec3Conv};
+
+/// Thresholds used to detect controller gestures
+#[derive(Resource, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GestureConfig {
+    /// linear speed in m/s a controller has to pass to count as a swing
+    pub swing_threshold: f32,
+    /// linear speed in m/s the controller has to move at between direction changes of a shake
+    pub shake_threshold: f32,
+    /// how many direction changes inside `shake_window` make a shake
+    pub shake_reversals: usize,
+    /// in seconds
+    pub shake_window: f32,
+    /// minimum time in seconds between two gesture events of the same kind and hand
+    pub cooldown: f32,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            swing_threshold: 3.0,
+            shake_threshold: 1.0,
+            shake_reversals: 3,
+            shake_window: 0.6,
+            cooldown: 0.4,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GestureKind {
+    Shake,
+    Swing,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ControllerGesture {
+    pub hand: Hand,
+    pub kind: GestureKind,
+}
+
+/// Raw grip velocity of a controller in stage space
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ControllerVelocity {
+    pub linear: Vec3,
+    pub angular: Vec3,
+    pub linear_valid: bool,
+    pub angular_valid: bool,
+}
+
+impl From<SpaceVelocity> for ControllerVelocity {
+    fn from(velocity: SpaceVelocity) -> Self {
+        Self {
+            linear: velocity.linear_velocity.to_vec3(),
+            angular: velocity.angular_velocity.to_vec3(),
+            linear_valid: velocity
+                .velocity_flags
+                .contains(SpaceVelocityFlags::LINEAR_VALID),
+            angular_valid: velocity
+                .velocity_flags
+                .contains(SpaceVelocityFlags::ANGULAR_VALID),
+        }
+    }
+}
+
+#[derive(Default)]
+struct HandGestureState {
+    velocity: ControllerVelocity,
+    // (seconds since startup, linear velocity)
+    history: VecDeque<(f32, Vec3)>,
+    was_swinging: bool,
+    last_shake: Option<f32>,
+    last_swing: Option<f32>,
+}
+
+impl HandGestureState {
+    fn reversals(&self, threshold: f32) -> usize {
+        let mut last_direction: Option<Vec3> = None;
+        let mut reversals = 0;
+        for (_, velocity) in self.history.iter() {
+            if velocity.length() < threshold {
+                continue;
+            }
+            if let Some(last) = last_direction {
+                if last.dot(*velocity) < 0.0 {
+                    reversals += 1;
+                }
+            }
+            last_direction = Some(*velocity);
+        }
+        reversals
+    }
+}
+
+/// Controller velocities and gesture helpers, updated every frame
+#[derive(Resource, Default)]
+pub struct ControllerGestures {
+    left: HandGestureState,
+    right: HandGestureState,
+    shake_reversals: usize,
+}
+
+impl ControllerGestures {
+    fn hand(&self, hand: Hand) -> &HandGestureState {
+        match hand {
+            Hand::Left => &self.left,
+            Hand::Right => &self.right,
+        }
+    }
+
+    fn hand_mut(&mut self, hand: Hand) -> &mut HandGestureState {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+
+    pub fn velocity(&self, hand: Hand) -> ControllerVelocity {
+        self.hand(hand).velocity
+    }
+
+    /// linear speed of the controller in m/s, 0 if the runtime has no valid velocity
+    pub fn swing_speed(&self, hand: Hand) -> f32 {
+        let velocity = self.velocity(hand);
+        match velocity.linear_valid {
+            true => velocity.linear.length(),
+            false => 0.0,
+        }
+    }
+
+    /// angular speed of the controller in rad/s
+    pub fn twist_speed(&self, hand: Hand) -> f32 {
+        let velocity = self.velocity(hand);
+        match velocity.angular_valid {
+            true => velocity.angular.length(),
+            false => 0.0,
+        }
+    }
+
+    /// whether the controller changed direction at least `GestureConfig::shake_reversals`
+    /// times inside the shake window while moving faster than `threshold`
+    pub fn is_shaking(&self, hand: Hand, threshold: f32) -> bool {
+        self.hand(hand).reversals(threshold) >= self.shake_reversals.max(1)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_controller_gestures(
+    config: Res<GestureConfig>,
+    time: Res<Time>,
+    mut gestures: ResMut<ControllerGestures>,
+    mut gesture_events: EventWriter<ControllerGesture>,
+    oculus_controller: Res<OculusController>,
+    frame_state: Res<XrFrameState>,
+    xr_input: Res<XrInput>,
+    session: Res<XrSession>,
+    action_sets: Res<XrActionSets>,
+) {
+    let frame_state = *frame_state.lock().unwrap();
+    let controller = oculus_controller.get_ref(&session, &frame_state, &xr_input, &action_sets);
+    let now = time.elapsed_seconds();
+    gestures.shake_reversals = config.shake_reversals;
+    for hand in [Hand::Left, Hand::Right] {
+        let velocity = ControllerVelocity::from(controller.grip_space(hand).1);
+        let state = gestures.hand_mut(hand);
+        state.velocity = velocity;
+        if velocity.linear_valid {
+            state.history.push_back((now, velocity.linear));
+        }
+        while state
+            .history
+            .front()
+            .is_some_and(|(t, _)| now - *t > config.shake_window)
+        {
+            state.history.pop_front();
+        }
+        let cooled_down = |last: Option<f32>| last.map_or(true, |t| now - t >= config.cooldown);
+
+        let swinging = velocity.linear_valid && velocity.linear.length() >= config.swing_threshold;
+        if swinging && !state.was_swinging && cooled_down(state.last_swing) {
+            state.last_swing = Some(now);
+            gesture_events.send(ControllerGesture {
+                hand,
+                kind: GestureKind::Swing,
+            });
+        }
+        state.was_swinging = swinging;
+
+        if state.reversals(config.shake_threshold) >= config.shake_reversals.max(1)
+            && cooled_down(state.last_shake)
+        {
+            state.last_shake = Some(now);
+            // start counting again, otherwise the same reversals trigger after the cooldown
+            state.history.clear();
+            gesture_events.send(ControllerGesture {
+                hand,
+                kind: GestureKind::Shake,
+            });
+        }
+    }
+}
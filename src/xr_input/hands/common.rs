@@ -1,11 +1,17 @@
 use bevy::prelude::{
-    default, Color, Commands, Component, Deref, DerefMut, Entity, Gizmos, Plugin, PostUpdate,
-    Query, Resource, SpatialBundle, Startup, Transform,
+    default, Color, Commands, Component, Deref, DerefMut, Entity, Gizmos, IntoSystemConfigs,
+    Plugin, PostUpdate, Query, Res, Resource, SpatialBundle, Startup, Transform, Update,
 };
+use bevy::transform::TransformSystem;
 
-use crate::xr_input::{trackers::OpenXRTracker, Hand};
+use crate::xr_init::xr_only;
+use crate::xr_input::Hand;
 
-use super::{BoneTrackingStatus, HandBone};
+use super::{
+    custom_skeleton::{map_custom_hand_skeletons, pose_custom_hand_skeletons},
+    pinch::{update_pinch_strength, PinchConfig, PinchEvent, PinchStrength},
+    BoneTrackingStatus, HandBone,
+};
 
 /// add debug renderer for controllers
 #[derive(Default)]
@@ -14,6 +20,17 @@ pub struct OpenXrHandInput;
 impl Plugin for OpenXrHandInput {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_systems(Startup, spawn_hand_entities);
+        //drive user rigged hand meshes from the hand bones
+        app.add_systems(Update, map_custom_hand_skeletons);
+        app.add_systems(
+            PostUpdate,
+            pose_custom_hand_skeletons.after(TransformSystem::TransformPropagate),
+        );
+        //source independent pinching
+        app.init_resource::<PinchConfig>();
+        app.init_resource::<PinchStrength>();
+        app.add_event::<PinchEvent>();
+        app.add_systems(Update, update_pinch_strength.run_if(xr_only()));
     }
 }
 
@@ -23,7 +40,64 @@ pub struct HandInputDebugRenderer;
 
 impl Plugin for HandInputDebugRenderer {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(PostUpdate, draw_hand_entities);
+        app.init_resource::<HandGizmoConfig>();
+        app.init_resource::<HandGizmosEnabled>();
+        app.add_systems(
+            PostUpdate,
+            draw_hand_entities.run_if(|enabled: Res<HandGizmosEnabled>| enabled.0),
+        );
+    }
+}
+
+/// Whether [`HandInputDebugRenderer`] draws the bones, the bones are updated either way
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HandGizmosEnabled(pub bool);
+
+impl Default for HandGizmosEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Colors of the hand bone gizmos per finger, and a factor on their [`HandBoneRadius`]
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct HandGizmoConfig {
+    pub palm: Color,
+    pub wrist: Color,
+    pub thumb: Color,
+    pub index: Color,
+    pub middle: Color,
+    pub ring: Color,
+    pub little: Color,
+    pub radius_multiplier: f32,
+}
+
+impl Default for HandGizmoConfig {
+    fn default() -> Self {
+        Self {
+            palm: Color::WHITE,
+            wrist: Color::GRAY,
+            thumb: Color::RED,
+            index: Color::ORANGE,
+            middle: Color::YELLOW,
+            ring: Color::GREEN,
+            little: Color::BLUE,
+            radius_multiplier: 1.0,
+        }
+    }
+}
+
+impl HandGizmoConfig {
+    pub fn color(&self, hand_bone: &HandBone) -> Color {
+        match hand_bone.get_index_from_bone() {
+            0 => self.palm,
+            1 => self.wrist,
+            2..=5 => self.thumb,
+            6..=10 => self.index,
+            11..=15 => self.middle,
+            16..=20 => self.ring,
+            _ => self.little,
+        }
     }
 }
 
@@ -163,10 +237,10 @@ pub fn spawn_hand_entities(mut commands: Commands) {
                 .spawn((
                     SpatialBundle::default(),
                     bone.clone(),
-                    OpenXRTracker,
                     hand.clone(),
                     BoneTrackingStatus::Emulated,
                     HandBoneRadius(0.1),
+                    HandBoneConfidence::EMULATED,
                 ))
                 .id();
             match hand {
@@ -239,17 +313,45 @@ pub fn spawn_hand_entities(mut commands: Commands) {
 #[derive(Debug, Component, DerefMut, Deref)]
 pub struct HandBoneRadius(pub f32);
 
+/// How much of the pose of a bone the runtime vouched for. Valid bones may still be inferred,
+/// e.g. an occluded fingertip, only tracked ones were actually observed.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq)]
+pub struct HandBoneConfidence {
+    pub position_valid: bool,
+    pub position_tracked: bool,
+    pub orientation_valid: bool,
+    pub orientation_tracked: bool,
+}
+
+impl HandBoneConfidence {
+    /// emulated bones are always valid, but never tracked
+    pub const EMULATED: Self = Self {
+        position_valid: true,
+        position_tracked: false,
+        orientation_valid: true,
+        orientation_tracked: false,
+    };
+
+    pub fn is_valid(&self) -> bool {
+        self.position_valid && self.orientation_valid
+    }
+
+    pub fn is_tracked(&self) -> bool {
+        self.position_tracked && self.orientation_tracked
+    }
+}
+
 pub fn draw_hand_entities(
     mut gizmos: Gizmos,
+    config: Res<HandGizmoConfig>,
     query: Query<(&Transform, &HandBone, &HandBoneRadius)>,
 ) {
     for (transform, hand_bone, hand_bone_radius) in query.iter() {
-        let (_, color) = get_bone_gizmo_style(hand_bone);
         gizmos.sphere(
             transform.translation,
             transform.rotation,
-            hand_bone_radius.0,
-            color,
+            hand_bone_radius.0 * config.radius_multiplier,
+            config.color(hand_bone),
         );
     }
 }
@@ -0,0 +1,151 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::xr_input::Hand;
+
+use super::HandBone;
+
+/// Names of the joints of a rigged hand mesh for every [`HandBone`].
+/// The default maps every bone to its own name, e.g. `IndexProximal`,
+/// which matches a mesh rigged after the built in skeleton.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandBoneNames(pub HashMap<HandBone, String>);
+
+impl Default for HandBoneNames {
+    fn default() -> Self {
+        Self(
+            HandBone::get_all_bones()
+                .into_iter()
+                .map(|bone| (bone, format!("{:?}", bone)))
+                .collect(),
+        )
+    }
+}
+
+impl HandBoneNames {
+    /// a mapping without any bones, add them with [`HandBoneNames::with`]
+    pub fn empty() -> Self {
+        Self(HashMap::new())
+    }
+
+    pub fn with(mut self, bone: HandBone, name: impl Into<String>) -> Self {
+        self.0.insert(bone, name.into());
+        self
+    }
+}
+
+/// Put this on the root of a rigged hand mesh (e.g. a gltf scene) to have its joints
+/// follow the hand bones. The joints are looked up by [`Name`] among the descendants,
+/// bones without a joint are skipped.
+#[derive(Component, Clone, Debug)]
+pub struct CustomHandSkeleton {
+    pub hand: Hand,
+    pub names: HandBoneNames,
+    /// applied to the rotation of every joint, for meshes whose joints don't point down -Z
+    pub rotation_offset: Quat,
+}
+
+impl CustomHandSkeleton {
+    pub fn new(hand: Hand, names: HandBoneNames) -> Self {
+        Self {
+            hand,
+            names,
+            rotation_offset: Quat::IDENTITY,
+        }
+    }
+}
+
+/// A joint of a [`CustomHandSkeleton`] that follows a hand bone, inserted automatically
+#[derive(Component, Clone, Copy, Debug)]
+pub struct MappedHandBone {
+    pub hand: Hand,
+    pub bone: HandBone,
+    pub rotation_offset: Quat,
+}
+
+/// Marks a [`CustomHandSkeleton`] whose joints have been found
+#[derive(Component)]
+pub struct CustomHandSkeletonMapped;
+
+/// scenes spawn their children later, so this retries until at least one joint is found
+pub fn map_custom_hand_skeletons(
+    mut commands: Commands,
+    skeletons: Query<(Entity, &CustomHandSkeleton), Without<CustomHandSkeletonMapped>>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+) {
+    for (entity, skeleton) in skeletons.iter() {
+        let mut found = Vec::new();
+        for descendant in children.iter_descendants(entity) {
+            let Ok(name) = names.get(descendant) else {
+                continue;
+            };
+            if let Some((bone, _)) = skeleton
+                .names
+                .0
+                .iter()
+                .find(|(_, n)| n.as_str() == name.as_str())
+            {
+                found.push(*bone);
+                commands.entity(descendant).insert(MappedHandBone {
+                    hand: skeleton.hand,
+                    bone: *bone,
+                    rotation_offset: skeleton.rotation_offset,
+                });
+            }
+        }
+        if found.is_empty() {
+            continue;
+        }
+        for (bone, name) in skeleton.names.0.iter() {
+            if !found.contains(bone) {
+                warn!(
+                    "no joint named {} for {:?} on custom hand skeleton",
+                    name, bone
+                );
+            }
+        }
+        commands.entity(entity).insert(CustomHandSkeletonMapped);
+    }
+}
+
+/// runs after transform propagation and writes both the local and global transform of the joints
+pub fn pose_custom_hand_skeletons(
+    mut joints: Query<
+        (Entity, &MappedHandBone, Option<&Parent>, &mut Transform),
+        Without<HandBone>,
+    >,
+    mut global_transforms: Query<&mut GlobalTransform, Without<HandBone>>,
+    bones: Query<(&GlobalTransform, &Hand, &HandBone)>,
+) {
+    let targets: HashMap<(Hand, HandBone), GlobalTransform> = bones
+        .iter()
+        .map(|(global, hand, bone)| ((*hand, *bone), *global))
+        .collect();
+    let mut posed: HashMap<Entity, GlobalTransform> = HashMap::new();
+    for (entity, mapped, _, _) in joints.iter() {
+        if let Some(target) = targets.get(&(mapped.hand, mapped.bone)) {
+            let target = target.compute_transform();
+            let rotation = target.rotation * mapped.rotation_offset;
+            posed.insert(entity, target.with_rotation(rotation).into());
+        }
+    }
+    for (entity, _, parent, mut transform) in joints.iter_mut() {
+        let Some(global) = posed.get(&entity) else {
+            continue;
+        };
+        // parents that are joints themselves already moved, so use their new pose
+        let parent_global = parent.and_then(|p| {
+            posed
+                .get(&p.get())
+                .copied()
+                .or_else(|| global_transforms.get(p.get()).ok().copied())
+        });
+        *transform = match parent_global {
+            Some(parent_global) => global.reparented_to(&parent_global),
+            None => global.compute_transform(),
+        };
+        if let Ok(mut global_transform) = global_transforms.get_mut(entity) {
+            *global_transform = *global;
+        }
+    }
+}
@@ -6,151 +6,701 @@ use openxr::{ActionTy, HandJoint};
 use super::common::{get_bone_gizmo_style, HandBoneRadius};
 use crate::{
     resources::{XrInstance, XrSession},
-    xr_init::{xr_only, XrSetup},
+    xr_init::{xr_input_active, xr_only, XrSetup},
     xr_input::{
         actions::{
             ActionHandednes, ActionType, SetupActionSet, SetupActionSets, XrActionSets, XrBinding,
         },
         hand_poses::get_simulated_open_hand_transforms,
-        trackers::{OpenXRLeftController, OpenXRRightController, OpenXRTrackingRoot},
+        smoothing::{smooth_transform, smoothing_alpha, CurlSmoothing},
+        trackers::{
+            GripPose, OpenXRLeftController, OpenXRRightController, OpenXRTrackingRoot, TrackingLost,
+        },
         Hand,
     },
 };
 
+use self::emulation_actions::{
+    FACE_BUTTON_TOUCH, INDEX_TOUCH, INDEX_VALUE, LITTLE_VALUE, MIDDLE_VALUE, RING_VALUE,
+    THUMBREST_TOUCH, THUMBSTICK_TOUCH, THUMB_TOUCH, THUMB_X, THUMB_Y,
+};
 use super::{BoneTrackingStatus, HandBone};
 
+/// A finger input with a touch sensor, `None` while the finger is off the controller
 pub enum TouchValue<T: ActionTy> {
     None,
     Touched(T),
 }
 
+impl TouchValue<f32> {
+    pub fn new(touched: bool, value: f32) -> Self {
+        // a pulled input is touched, even if the touch sensor missed it
+        match touched || value > 0.0 {
+            true => TouchValue::Touched(value),
+            false => TouchValue::None,
+        }
+    }
+
+    /// a finger resting on the input without pulling is already curled by `touch_curl`
+    pub fn curl(&self, touch_curl: f32) -> f32 {
+        match self {
+            TouchValue::None => 0.0,
+            TouchValue::Touched(value) => touch_curl + value * (1.0 - touch_curl),
+        }
+    }
+}
+
 pub struct HandEmulationPlugin;
 
 impl Plugin for HandEmulationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_hand_skeleton_from_emulated.run_if(xr_only()));
+        app.init_resource::<ThumbRestPoses>();
+        app.init_resource::<HandEmulationConfig>();
+        app.init_resource::<HandSplayConfig>();
+        app.init_resource::<MissingControllerPolicy>();
+        app.init_resource::<CurlSmoothing>();
+        app.init_resource::<EmulatedHandInputs>();
+        app.add_systems(
+            Update,
+            (
+                read_emulated_hand_inputs,
+                update_hand_skeleton_from_emulated,
+            )
+                .chain()
+                .run_if(xr_only())
+                .run_if(xr_input_active),
+        );
         app.add_systems(XrSetup, setup_hand_emulation_action_set);
     }
 }
 
-const HAND_ACTION_SET: &str = "hand_pose_approx";
+pub(crate) const HAND_ACTION_SET: &str = "hand_pose_approx";
+
+/// Names of the actions of the hand emulation action set, to bind in [`HandEmulationProfiles`]
+pub mod emulation_actions {
+    /// bool, the thumb touches anything
+    pub const THUMB_TOUCH: &str = "thumb_touch";
+    /// bool, the thumb rests on the thumbstick
+    pub const THUMBSTICK_TOUCH: &str = "thumbstick_touch";
+    /// bool, the thumb rests on the thumbrest
+    pub const THUMBREST_TOUCH: &str = "thumbrest_touch";
+    /// bool, the thumb rests on the face buttons
+    pub const FACE_BUTTON_TOUCH: &str = "face_button_touch";
+    /// f32, thumbstick x, moves the thumb sideways
+    pub const THUMB_X: &str = "thumb_x";
+    /// f32, thumbstick y, moves the thumb forwards
+    pub const THUMB_Y: &str = "thumb_y";
+    /// bool, the index finger touches the trigger
+    pub const INDEX_TOUCH: &str = "index_touch";
+    /// f32, curl of the index finger
+    pub const INDEX_VALUE: &str = "index_value";
+    /// f32, curl of the middle finger
+    pub const MIDDLE_VALUE: &str = "middle_value";
+    /// f32, curl of the ring finger
+    pub const RING_VALUE: &str = "ring_value";
+    /// f32, curl of the little finger
+    pub const LITTLE_VALUE: &str = "little_value";
+}
+
+/// Extra interaction profiles for hand emulation, for controllers the crate doesn't know.
+/// Insert it before `XrSetup`, the bindings use the actions in [`emulation_actions`].
+#[derive(Resource, Clone, Debug, Default)]
+pub struct HandEmulationProfiles(pub Vec<(&'static str, Vec<XrBinding>)>);
 
-fn setup_hand_emulation_action_set(mut action_sets: ResMut<SetupActionSets>) {
+impl HandEmulationProfiles {
+    pub fn with(mut self, profile: &'static str, bindings: Vec<XrBinding>) -> Self {
+        self.0.push((profile, bindings));
+        self
+    }
+}
+
+fn setup_hand_emulation_action_set(
+    mut action_sets: ResMut<SetupActionSets>,
+    profiles: Option<Res<HandEmulationProfiles>>,
+) {
     let action_set =
         action_sets.add_action_set(HAND_ACTION_SET, "Hand Pose Approximaiton".into(), 0);
     action_set.new_action(
-        "thumb_touch",
+        THUMB_TOUCH,
         "Thumb Touched".into(),
         ActionType::Bool,
         ActionHandednes::Double,
     );
     action_set.new_action(
-        "thumb_x",
+        THUMBSTICK_TOUCH,
+        "Thumb On Thumbstick".into(),
+        ActionType::Bool,
+        ActionHandednes::Double,
+    );
+    action_set.new_action(
+        THUMBREST_TOUCH,
+        "Thumb On Thumbrest".into(),
+        ActionType::Bool,
+        ActionHandednes::Double,
+    );
+    action_set.new_action(
+        FACE_BUTTON_TOUCH,
+        "Thumb On Face Buttons".into(),
+        ActionType::Bool,
+        ActionHandednes::Double,
+    );
+    action_set.new_action(
+        THUMB_X,
         "Thumb X".into(),
         ActionType::F32,
         ActionHandednes::Double,
     );
     action_set.new_action(
-        "thumb_y",
+        THUMB_Y,
         "Thumb Y".into(),
         ActionType::F32,
         ActionHandednes::Double,
     );
 
     action_set.new_action(
-        "index_touch",
+        INDEX_TOUCH,
         "Index Finger Touched".into(),
         ActionType::Bool,
         ActionHandednes::Double,
     );
     action_set.new_action(
-        "index_value",
+        INDEX_VALUE,
         "Index Finger Pull".into(),
         ActionType::F32,
         ActionHandednes::Double,
     );
 
     action_set.new_action(
-        "middle_value",
+        MIDDLE_VALUE,
         "Middle Finger Pull".into(),
         ActionType::F32,
         ActionHandednes::Double,
     );
     action_set.new_action(
-        "ring_value",
+        RING_VALUE,
         "Ring Finger Pull".into(),
         ActionType::F32,
         ActionHandednes::Double,
     );
     action_set.new_action(
-        "little_value",
+        LITTLE_VALUE,
         "Little Finger Pull".into(),
         ActionType::F32,
         ActionHandednes::Double,
     );
 
     suggest_oculus_touch_profile(action_set);
+    suggest_vive_cosmos_profile(action_set);
+    suggest_vive_focus3_profile(action_set);
+    suggest_valve_index_profile(action_set);
+    suggest_vive_profile(action_set);
+    suggest_hp_reverb_profile(action_set);
+    suggest_wmr_profile(action_set);
+    suggest_touch_controller_pro_profile(action_set);
+    suggest_simple_profile(action_set);
+    for (profile, bindings) in profiles.iter().flat_map(|p| p.0.iter()) {
+        action_set.suggest_binding(profile, bindings);
+    }
+}
+
+/// The finger state an emulated hand is posed from, see [`update_hand_bones_emulated`].
+/// Curls go from 0.0 for a straight finger to 1.0 for a fist,
+/// `thumb_splay` is in degrees and added to [`HandSplayConfig::thumb`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EmulatedHandPoseData {
+    pub thumb_curl: f32,
+    pub thumb_splay: f32,
+    pub index_curl: f32,
+    pub middle_curl: f32,
+    pub ring_curl: f32,
+    pub little_curl: f32,
+    /// see [`ThumbRestPose::offset`]
+    pub thumb_offset: Transform,
 }
 
-pub struct EmulatedHandPoseData {}
+/// The control the thumb is currently resting on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThumbSurface {
+    #[default]
+    None,
+    Thumbstick,
+    Thumbrest,
+    FaceButtons,
+}
+
+/// How the emulated thumb sits on a surface,
+/// `splay` is in degrees and added to the default thumb splay
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThumbRestPose {
+    pub curl: f32,
+    pub splay: f32,
+    /// moves and turns the whole thumb from its base, in the space of the right palm with
+    /// -Z along the fingers, mirrored for the left hand
+    pub offset: Transform,
+}
+
+/// Rest pose of the emulated thumb for every [`ThumbSurface`], overwrite to tune them
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct ThumbRestPoses {
+    pub none: ThumbRestPose,
+    pub thumbstick: ThumbRestPose,
+    pub thumbrest: ThumbRestPose,
+    pub face_buttons: ThumbRestPose,
+}
+
+impl Default for ThumbRestPoses {
+    fn default() -> Self {
+        Self {
+            none: ThumbRestPose {
+                curl: 0.0,
+                splay: 0.0,
+                offset: Transform::IDENTITY,
+            },
+            thumbstick: ThumbRestPose {
+                curl: 1.0,
+                splay: 0.0,
+                offset: Transform::from_xyz(0.0, 0.005, 0.0),
+            },
+            // sits further back than the stick
+            thumbrest: ThumbRestPose {
+                curl: 0.7,
+                splay: -5.0,
+                offset: Transform::from_xyz(0.0, 0.005, 0.01),
+            },
+            // reaches a little further out and lies flatter on the buttons
+            face_buttons: ThumbRestPose {
+                curl: 1.0,
+                splay: 8.0,
+                offset: Transform::from_xyz(0.005, 0.0, -0.005)
+                    .with_rotation(Quat::from_rotation_x(5.0 * PI / 180.0)),
+            },
+        }
+    }
+}
+
+impl ThumbRestPoses {
+    pub fn get(&self, surface: ThumbSurface) -> ThumbRestPose {
+        match surface {
+            ThumbSurface::None => self.none,
+            ThumbSurface::Thumbstick => self.thumbstick,
+            ThumbSurface::Thumbrest => self.thumbrest,
+            ThumbSurface::FaceButtons => self.face_buttons,
+        }
+    }
+}
+
+/// What the emulated bones of a hand do while its controller has no pose,
+/// e.g. because it went to sleep or lost tracking
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingControllerPolicy {
+    /// hide the bones until the controller is back
+    Hide,
+    /// the bones stay where the controller was last seen
+    #[default]
+    HoldLast,
+    /// every bone goes to the origin of the tracking root
+    Collapse,
+}
+
+/// How far the emulated fingers curl, in degrees per joint.
+/// A joint bends by `base_curl_degrees + curl * max_curl_degrees * multiplier` of its finger,
+/// the proximal joints never curl.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct HandEmulationConfig {
+    pub max_curl_degrees: f32,
+    pub base_curl_degrees: f32,
+    pub thumb_multiplier: f32,
+    pub index_multiplier: f32,
+    pub middle_multiplier: f32,
+    pub ring_multiplier: f32,
+    pub little_multiplier: f32,
+    /// curl from 0.0 to 1.0 of a finger touching its input without pulling it,
+    /// only for inputs with a touch sensor
+    pub touch_curl: f32,
+}
+
+/// Splay of each emulated finger in degrees, positive spreads away from the little finger.
+/// The thumb splay of the current [`ThumbRestPose`] is added to `thumb`.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct HandSplayConfig {
+    pub thumb: f32,
+    pub index: f32,
+    pub middle: f32,
+    pub ring: f32,
+    pub little: f32,
+}
+
+impl Default for HandSplayConfig {
+    fn default() -> Self {
+        Self {
+            thumb: 30.0,
+            index: 10.0,
+            middle: 0.0,
+            ring: -10.0,
+            little: -20.0,
+        }
+    }
+}
+
+impl Default for HandEmulationConfig {
+    fn default() -> Self {
+        Self {
+            max_curl_degrees: 80.0,
+            base_curl_degrees: 5.0,
+            thumb_multiplier: 0.1,
+            index_multiplier: 1.0,
+            middle_multiplier: 1.0,
+            ring_multiplier: 1.0,
+            little_multiplier: 1.0,
+            touch_curl: 0.2,
+        }
+    }
+}
 
 fn suggest_oculus_touch_profile(action_set: &mut SetupActionSet) {
     action_set.suggest_binding(
         "/interaction_profiles/oculus/touch_controller",
         &[
-            XrBinding::new("thumb_x", "/user/hand/left/input/thumbstick/x"),
-            XrBinding::new("thumb_x", "/user/hand/right/input/thumbstick/x"),
-            XrBinding::new("thumb_y", "/user/hand/left/input/thumbstick/y"),
-            XrBinding::new("thumb_y", "/user/hand/right/input/thumbstick/y"),
-            XrBinding::new("thumb_touch", "/user/hand/left/input/thumbstick/touch"),
-            XrBinding::new("thumb_touch", "/user/hand/right/input/thumbstick/touch"),
-            XrBinding::new("thumb_touch", "/user/hand/left/input/x/touch"),
-            XrBinding::new("thumb_touch", "/user/hand/left/input/y/touch"),
-            XrBinding::new("thumb_touch", "/user/hand/right/input/a/touch"),
-            XrBinding::new("thumb_touch", "/user/hand/right/input/b/touch"),
-            XrBinding::new("thumb_touch", "/user/hand/left/input/thumbrest/touch"),
-            XrBinding::new("thumb_touch", "/user/hand/right/input/thumbrest/touch"),
-            XrBinding::new("index_touch", "/user/hand/left/input/trigger/touch"),
-            XrBinding::new("index_value", "/user/hand/left/input/trigger/value"),
-            XrBinding::new("index_touch", "/user/hand/right/input/trigger/touch"),
-            XrBinding::new("index_value", "/user/hand/right/input/trigger/value"),
-            XrBinding::new("middle_value", "/user/hand/left/input/squeeze/value"),
-            XrBinding::new("middle_value", "/user/hand/right/input/squeeze/value"),
-            XrBinding::new("ring_value", "/user/hand/left/input/squeeze/value"),
-            XrBinding::new("ring_value", "/user/hand/right/input/squeeze/value"),
-            XrBinding::new("little_value", "/user/hand/left/input/squeeze/value"),
-            XrBinding::new("little_value", "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(THUMB_X, "/user/hand/left/input/thumbstick/x"),
+            XrBinding::new(THUMB_X, "/user/hand/right/input/thumbstick/x"),
+            XrBinding::new(THUMB_Y, "/user/hand/left/input/thumbstick/y"),
+            XrBinding::new(THUMB_Y, "/user/hand/right/input/thumbstick/y"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/thumbstick/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/thumbstick/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/x/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/y/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/a/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/b/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/thumbrest/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/thumbrest/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/left/input/thumbstick/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/right/input/thumbstick/touch"),
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/left/input/thumbrest/touch"),
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/right/input/thumbrest/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/x/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/y/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/a/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/b/touch"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/left/input/trigger/touch"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/right/input/trigger/touch"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/right/input/squeeze/value"),
         ],
     );
 }
 
-#[allow(clippy::type_complexity)]
-pub(crate) fn update_hand_skeleton_from_emulated(
+// the cosmos grip is a button, so middle/ring/little snap between open and closed
+fn suggest_vive_cosmos_profile(action_set: &mut SetupActionSet) {
+    action_set.suggest_binding(
+        "/interaction_profiles/htc/vive_cosmos_controller",
+        &[
+            XrBinding::new(THUMB_X, "/user/hand/left/input/thumbstick/x"),
+            XrBinding::new(THUMB_X, "/user/hand/right/input/thumbstick/x"),
+            XrBinding::new(THUMB_Y, "/user/hand/left/input/thumbstick/y"),
+            XrBinding::new(THUMB_Y, "/user/hand/right/input/thumbstick/y"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/thumbstick/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/thumbstick/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/x/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/y/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/a/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/b/click"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/left/input/thumbstick/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/right/input/thumbstick/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/x/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/y/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/a/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/b/click"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/left/input/trigger/click"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/right/input/trigger/click"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/left/input/squeeze/click"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/right/input/squeeze/click"),
+            XrBinding::new(RING_VALUE, "/user/hand/left/input/squeeze/click"),
+            XrBinding::new(RING_VALUE, "/user/hand/right/input/squeeze/click"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/left/input/squeeze/click"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/right/input/squeeze/click"),
+        ],
+    );
+}
+
+fn suggest_vive_focus3_profile(action_set: &mut SetupActionSet) {
+    action_set.suggest_binding(
+        "/interaction_profiles/htc/vive_focus3_controller",
+        &[
+            XrBinding::new(THUMB_X, "/user/hand/left/input/thumbstick/x"),
+            XrBinding::new(THUMB_X, "/user/hand/right/input/thumbstick/x"),
+            XrBinding::new(THUMB_Y, "/user/hand/left/input/thumbstick/y"),
+            XrBinding::new(THUMB_Y, "/user/hand/right/input/thumbstick/y"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/thumbstick/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/thumbstick/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/thumbrest/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/thumbrest/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/x/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/y/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/a/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/b/click"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/left/input/thumbstick/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/right/input/thumbstick/touch"),
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/left/input/thumbrest/touch"),
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/right/input/thumbrest/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/x/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/y/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/a/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/b/click"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/left/input/trigger/touch"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/right/input/trigger/touch"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/right/input/squeeze/value"),
+        ],
+    );
+}
+
+// openxr doesn't expose the per finger capsense of the index controller,
+// the runtime folds middle/ring/little into squeeze/value so they still share the grip
+fn suggest_valve_index_profile(action_set: &mut SetupActionSet) {
+    action_set.suggest_binding(
+        "/interaction_profiles/valve/index_controller",
+        &[
+            XrBinding::new(THUMB_X, "/user/hand/left/input/thumbstick/x"),
+            XrBinding::new(THUMB_X, "/user/hand/right/input/thumbstick/x"),
+            XrBinding::new(THUMB_Y, "/user/hand/left/input/thumbstick/y"),
+            XrBinding::new(THUMB_Y, "/user/hand/right/input/thumbstick/y"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/thumbstick/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/thumbstick/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/trackpad/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/trackpad/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/a/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/b/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/a/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/b/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/left/input/thumbstick/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/right/input/thumbstick/touch"),
+            // the trackpad sits where other controllers have their thumbrest
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/left/input/trackpad/touch"),
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/right/input/trackpad/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/a/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/b/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/a/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/b/touch"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/left/input/trigger/touch"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/right/input/trigger/touch"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/right/input/squeeze/value"),
+        ],
+    );
+}
+
+// the wand has no per finger input, so middle/ring/little all follow the squeeze click
+// and snap between open and closed. the trackpad stands in for the thumbstick
+fn suggest_vive_profile(action_set: &mut SetupActionSet) {
+    action_set.suggest_binding(
+        "/interaction_profiles/htc/vive_controller",
+        &[
+            XrBinding::new(THUMB_X, "/user/hand/left/input/trackpad/x"),
+            XrBinding::new(THUMB_X, "/user/hand/right/input/trackpad/x"),
+            XrBinding::new(THUMB_Y, "/user/hand/left/input/trackpad/y"),
+            XrBinding::new(THUMB_Y, "/user/hand/right/input/trackpad/y"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/trackpad/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/trackpad/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/left/input/trackpad/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/right/input/trackpad/touch"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/left/input/trigger/click"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/right/input/trigger/click"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/left/input/squeeze/click"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/right/input/squeeze/click"),
+            XrBinding::new(RING_VALUE, "/user/hand/left/input/squeeze/click"),
+            XrBinding::new(RING_VALUE, "/user/hand/right/input/squeeze/click"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/left/input/squeeze/click"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/right/input/squeeze/click"),
+        ],
+    );
+}
+
+// the g2 has no capacitive sensors, so clicks stand in for touches.
+// squeeze/value is analog on this controller, unlike the squeeze/click of the generic wmr profile
+fn suggest_hp_reverb_profile(action_set: &mut SetupActionSet) {
+    action_set.suggest_binding(
+        "/interaction_profiles/hp/mixed_reality_controller",
+        &[
+            XrBinding::new(THUMB_X, "/user/hand/left/input/thumbstick/x"),
+            XrBinding::new(THUMB_X, "/user/hand/right/input/thumbstick/x"),
+            XrBinding::new(THUMB_Y, "/user/hand/left/input/thumbstick/y"),
+            XrBinding::new(THUMB_Y, "/user/hand/right/input/thumbstick/y"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/thumbstick/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/thumbstick/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/x/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/y/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/a/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/b/click"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/left/input/thumbstick/click"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/right/input/thumbstick/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/x/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/y/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/a/click"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/b/click"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/right/input/squeeze/value"),
+        ],
+    );
+}
+
+// the first wmr controllers have no capacitive sticks and only a squeeze click,
+// the trackpad is the only thing that senses the thumb without pressing
+fn suggest_wmr_profile(action_set: &mut SetupActionSet) {
+    action_set.suggest_binding(
+        "/interaction_profiles/microsoft/motion_controller",
+        &[
+            XrBinding::new(THUMB_X, "/user/hand/left/input/thumbstick/x"),
+            XrBinding::new(THUMB_X, "/user/hand/right/input/thumbstick/x"),
+            XrBinding::new(THUMB_Y, "/user/hand/left/input/thumbstick/y"),
+            XrBinding::new(THUMB_Y, "/user/hand/right/input/thumbstick/y"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/thumbstick/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/thumbstick/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/trackpad/touch"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/trackpad/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/left/input/thumbstick/click"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/right/input/thumbstick/click"),
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/left/input/trackpad/touch"),
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/right/input/trackpad/touch"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/left/input/squeeze/click"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/right/input/squeeze/click"),
+            XrBinding::new(RING_VALUE, "/user/hand/left/input/squeeze/click"),
+            XrBinding::new(RING_VALUE, "/user/hand/right/input/squeeze/click"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/left/input/squeeze/click"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/right/input/squeeze/click"),
+        ],
+    );
+}
+
+// the touch pro senses the index finger off the trigger (curl_fb, proximity_fb) and the
+// thumb above the face (thumb_fb/proximity_fb), the other fingers only share the grip.
+// needs XR_FB_touch_controller_pro, the suggestion is skipped if the runtime doesn't have it
+fn suggest_touch_controller_pro_profile(action_set: &mut SetupActionSet) {
+    action_set.suggest_binding(
+        "/interaction_profiles/facebook/touch_controller_pro",
+        &[
+            XrBinding::new(THUMB_X, "/user/hand/left/input/thumbstick/x"),
+            XrBinding::new(THUMB_X, "/user/hand/right/input/thumbstick/x"),
+            XrBinding::new(THUMB_Y, "/user/hand/left/input/thumbstick/y"),
+            XrBinding::new(THUMB_Y, "/user/hand/right/input/thumbstick/y"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/thumb_fb/proximity_fb"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/thumb_fb/proximity_fb"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/left/input/thumbstick/touch"),
+            XrBinding::new(THUMBSTICK_TOUCH, "/user/hand/right/input/thumbstick/touch"),
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/left/input/thumbrest/touch"),
+            XrBinding::new(THUMBREST_TOUCH, "/user/hand/right/input/thumbrest/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/x/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/left/input/y/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/a/touch"),
+            XrBinding::new(FACE_BUTTON_TOUCH, "/user/hand/right/input/b/touch"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/left/input/trigger/proximity_fb"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/right/input/trigger/proximity_fb"),
+            // the runtime uses the larger of the two, so the finger curls even off the trigger
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/trigger/value"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/trigger/curl_fb"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/trigger/value"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/trigger/curl_fb"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(MIDDLE_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(RING_VALUE, "/user/hand/right/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/left/input/squeeze/value"),
+            XrBinding::new(LITTLE_VALUE, "/user/hand/right/input/squeeze/value"),
+        ],
+    );
+}
+
+// last resort for runtimes without any of the profiles above, select only opens and closes
+// the index finger, the hand would look broken if the whole fist did
+fn suggest_simple_profile(action_set: &mut SetupActionSet) {
+    action_set.suggest_binding(
+        "/interaction_profiles/khr/simple_controller",
+        &[
+            XrBinding::new(THUMB_TOUCH, "/user/hand/left/input/menu/click"),
+            XrBinding::new(THUMB_TOUCH, "/user/hand/right/input/menu/click"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/left/input/select/click"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/left/input/select/click"),
+            XrBinding::new(INDEX_TOUCH, "/user/hand/right/input/select/click"),
+            XrBinding::new(INDEX_VALUE, "/user/hand/right/input/select/click"),
+        ],
+    );
+}
+
+/// The finger inputs of one controller an emulated hand is posed from, curls go from
+/// 0.0 to 1.0 like in [`EmulatedHandPoseData`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EmulatedFingerInput {
+    pub thumb_surface: ThumbSurface,
+    pub index_curl: f32,
+    pub middle_curl: f32,
+    pub ring_curl: f32,
+    pub little_curl: f32,
+}
+
+/// The finger inputs of both hands, read from the hand emulation action set every frame
+/// by [`read_emulated_hand_inputs`], or written by the mock backend
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EmulatedHandInputs {
+    pub left: EmulatedFingerInput,
+    pub right: EmulatedFingerInput,
+}
+
+impl EmulatedHandInputs {
+    pub fn get(&self, hand: Hand) -> EmulatedFingerInput {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+
+    pub fn set(&mut self, hand: Hand, input: EmulatedFingerInput) {
+        match hand {
+            Hand::Left => self.left = input,
+            Hand::Right => self.right = input,
+        }
+    }
+}
+
+pub(crate) fn read_emulated_hand_inputs(
     session: Res<XrSession>,
     instance: Res<XrInstance>,
     action_sets: Res<XrActionSets>,
-    left_controller_transform: Query<&Transform, With<OpenXRLeftController>>,
-    right_controller_transform: Query<&Transform, With<OpenXRRightController>>,
-    tracking_root_transform: Query<&Transform, With<OpenXRTrackingRoot>>,
-    mut bones: Query<
-        (
-            &mut Transform,
-            &HandBone,
-            &Hand,
-            &BoneTrackingStatus,
-            &mut HandBoneRadius,
-        ),
-        (
-            Without<OpenXRLeftController>,
-            Without<OpenXRRightController>,
-            Without<OpenXRTrackingRoot>,
-        ),
-    >,
+    config: Res<HandEmulationConfig>,
+    mut inputs: ResMut<EmulatedHandInputs>,
 ) {
-    //get the transforms outside the loop
-    let left = left_controller_transform.get_single();
-    let right = right_controller_transform.get_single();
-    let mut data: [[Transform; 26]; 2] = [[Transform::default(); 26]; 2];
     for (subaction_path, hand) in [
         (
             instance.string_to_path("/user/hand/left").unwrap(),
@@ -161,51 +711,166 @@ pub(crate) fn update_hand_skeleton_from_emulated(
             Hand::Right,
         ),
     ] {
-        let thumb_curl = match action_sets
-            .get_action_bool(HAND_ACTION_SET, "thumb_touch")
-            .unwrap()
-            .state(&session, subaction_path)
-            .unwrap()
-            .current_state
-        {
-            true => 1.0,
-            false => 0.0,
+        let touched = |action: &'static str| {
+            action_sets
+                .get_action_bool(HAND_ACTION_SET, action)
+                .unwrap()
+                .state(&session, subaction_path)
+                .unwrap()
+                .current_state
+        };
+        let thumb_surface = if touched(THUMBSTICK_TOUCH) {
+            ThumbSurface::Thumbstick
+        } else if touched(FACE_BUTTON_TOUCH) {
+            ThumbSurface::FaceButtons
+        } else if touched(THUMBREST_TOUCH) {
+            ThumbSurface::Thumbrest
+        } else if touched(THUMB_TOUCH) {
+            // touching something we don't have a separate binding for
+            ThumbSurface::Thumbstick
+        } else {
+            ThumbSurface::None
         };
-        let index_curl = action_sets
-            .get_action_f32(HAND_ACTION_SET, "index_value")
+        let index_value = action_sets
+            .get_action_f32(HAND_ACTION_SET, INDEX_VALUE)
             .unwrap()
             .state(&session, subaction_path)
             .unwrap()
             .current_state;
+        let index_touch = action_sets
+            .get_action_bool(HAND_ACTION_SET, INDEX_TOUCH)
+            .unwrap()
+            .state(&session, subaction_path)
+            .unwrap();
+        // without a touch sensor the value is all there is
+        let index_curl = match index_touch.is_active {
+            true => TouchValue::new(index_touch.current_state, index_value).curl(config.touch_curl),
+            false => index_value,
+        };
         let middle_curl = action_sets
-            .get_action_f32(HAND_ACTION_SET, "middle_value")
+            .get_action_f32(HAND_ACTION_SET, MIDDLE_VALUE)
             .unwrap()
             .state(&session, subaction_path)
             .unwrap()
             .current_state;
         let ring_curl = action_sets
-            .get_action_f32(HAND_ACTION_SET, "ring_value")
+            .get_action_f32(HAND_ACTION_SET, RING_VALUE)
             .unwrap()
             .state(&session, subaction_path)
             .unwrap()
             .current_state;
         let little_curl = action_sets
-            .get_action_f32(HAND_ACTION_SET, "little_value")
+            .get_action_f32(HAND_ACTION_SET, LITTLE_VALUE)
             .unwrap()
             .state(&session, subaction_path)
             .unwrap()
             .current_state;
+        inputs.set(
+            hand,
+            EmulatedFingerInput {
+                thumb_surface,
+                index_curl,
+                middle_curl,
+                ring_curl,
+                little_curl,
+            },
+        );
+    }
+}
+
+// reads the controller poses, which hold while unfocused, so it likely wants
+// `xr_focused()` as well once the hands shouldn't curl from stale actions either
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn update_hand_skeleton_from_emulated(
+    inputs: Res<EmulatedHandInputs>,
+    thumb_rest_poses: Res<ThumbRestPoses>,
+    config: Res<HandEmulationConfig>,
+    splay_config: Res<HandSplayConfig>,
+    curl_smoothing: Res<CurlSmoothing>,
+    time: Res<Time>,
+    mut smoothed_inputs: Local<Option<[[f32; 6]; 2]>>,
+    mut smoothed_thumb_offsets: Local<Option<[Transform; 2]>>,
+    missing_policy: Res<MissingControllerPolicy>,
+    left_controller_transform: Query<
+        (&Transform, Option<&GripPose>),
+        (With<OpenXRLeftController>, Without<TrackingLost>),
+    >,
+    right_controller_transform: Query<
+        (&Transform, Option<&GripPose>),
+        (With<OpenXRRightController>, Without<TrackingLost>),
+    >,
+    tracking_root_transform: Query<&Transform, With<OpenXRTrackingRoot>>,
+    mut bones: Query<
+        (
+            &mut Transform,
+            &HandBone,
+            &Hand,
+            &BoneTrackingStatus,
+            &mut HandBoneRadius,
+            Option<&mut Visibility>,
+        ),
+        (
+            Without<OpenXRLeftController>,
+            Without<OpenXRRightController>,
+            Without<OpenXRTrackingRoot>,
+        ),
+    >,
+) {
+    //get the transforms outside the loop, the grip if the transform follows another pose
+    let grip = |(transform, grip): (&Transform, Option<&GripPose>)| match grip {
+        Some(grip) => grip.0.with_scale(transform.scale),
+        None => *transform,
+    };
+    let left = left_controller_transform.get_single().map(grip);
+    let right = right_controller_transform.get_single().map(grip);
+    let has_controller = [left.is_ok(), right.is_ok()];
+    let mut data: [[Transform; 26]; 2] = [[Transform::default(); 26]; 2];
+    for hand in [Hand::Left, Hand::Right] {
+        let EmulatedFingerInput {
+            thumb_surface,
+            index_curl,
+            middle_curl,
+            ring_curl,
+            little_curl,
+        } = inputs.get(hand);
+        let thumb_pose = thumb_rest_poses.get(thumb_surface);
+        // thumb curl, thumb splay and the four finger curls, the thumb eases between
+        // its rest poses instead of jumping when it touches something
+        let target = [
+            thumb_pose.curl,
+            thumb_pose.splay,
+            index_curl,
+            middle_curl,
+            ring_curl,
+            little_curl,
+        ];
+        let alpha = smoothing_alpha(curl_smoothing.time_constant, time.delta_seconds());
+        let smoothed = &mut smoothed_inputs.get_or_insert([target; 2])[hand as usize];
+        for (current, target) in smoothed.iter_mut().zip(target) {
+            *current += (target - *current) * alpha;
+        }
+        let [thumb_curl, thumb_splay, index_curl, middle_curl, ring_curl, little_curl] = *smoothed;
+        let thumb_offset =
+            &mut smoothed_thumb_offsets.get_or_insert([thumb_pose.offset; 2])[hand as usize];
+        *thumb_offset = smooth_transform(thumb_offset, thumb_pose.offset, alpha);
+        let pose = EmulatedHandPoseData {
+            thumb_curl,
+            thumb_splay,
+            index_curl,
+            middle_curl,
+            ring_curl,
+            little_curl,
+            thumb_offset: *thumb_offset,
+        };
         match hand {
             Hand::Left => match left {
                 Ok(hand_transform) => {
                     data[0] = update_hand_bones_emulated(
-                        hand_transform,
+                        &hand_transform,
                         hand,
-                        thumb_curl,
-                        index_curl,
-                        middle_curl,
-                        ring_curl,
-                        little_curl,
+                        &pose,
+                        &config,
+                        &splay_config,
                     );
                 }
                 Err(_) => debug!("no left controller transform for hand bone emulation"),
@@ -213,24 +878,42 @@ pub(crate) fn update_hand_skeleton_from_emulated(
             Hand::Right => match right {
                 Ok(hand_transform) => {
                     data[1] = update_hand_bones_emulated(
-                        hand_transform,
+                        &hand_transform,
                         hand,
-                        thumb_curl,
-                        index_curl,
-                        middle_curl,
-                        ring_curl,
-                        little_curl,
+                        &pose,
+                        &config,
+                        &splay_config,
                     );
                 }
                 Err(_) => debug!("no right controller transform for hand bone emulation"),
             },
         }
     }
-    let trt = tracking_root_transform.single();
-    for (mut t, bone, hand, status, mut radius) in bones.iter_mut() {
-        match status {
-            BoneTrackingStatus::Emulated => {}
-            BoneTrackingStatus::Tracked => continue,
+    // the controllers are relative to the root but the bones are in world space,
+    // so the root is applied here. without a root the stage is the world
+    let trt = tracking_root_transform
+        .get_single()
+        .copied()
+        .unwrap_or_default();
+    for (mut t, bone, hand, status, mut radius, visibility) in bones.iter_mut() {
+        let tracked = *status == BoneTrackingStatus::Tracked;
+        let has_controller = has_controller[*hand as usize];
+        // tracked bones are shown again even if their controller is gone
+        if let (MissingControllerPolicy::Hide, Some(mut visibility)) = (*missing_policy, visibility)
+        {
+            let wanted = match tracked || has_controller {
+                true => Visibility::Inherited,
+                false => Visibility::Hidden,
+            };
+            if *visibility != wanted {
+                *visibility = wanted;
+            }
+        }
+        if tracked {
+            continue;
+        }
+        if !has_controller && *missing_policy != MissingControllerPolicy::Collapse {
+            continue;
         }
         radius.0 = get_bone_gizmo_style(bone).0;
 
@@ -243,14 +926,15 @@ pub(crate) fn update_hand_skeleton_from_emulated(
         *t = t.with_translation(trt.transform_point(t.translation));
     }
 }
+/// Poses the 26 bones of an emulated hand from its controller, without touching the ECS.
+/// The bones are relative to the tracking root like the controller transform and oriented like
+/// tracked joints, looking along -Z towards the next joint, so they can drive a rigged hand.
 pub fn update_hand_bones_emulated(
     controller_transform: &Transform,
     hand: Hand,
-    thumb_curl: f32,
-    index_curl: f32,
-    middle_curl: f32,
-    ring_curl: f32,
-    little_curl: f32,
+    pose: &EmulatedHandPoseData,
+    config: &HandEmulationConfig,
+    splay_config: &HandSplayConfig,
 ) -> [Transform; 26] {
     let left_hand_rot = Quat::from_rotation_y(PI);
     let hand_translation: Vec3 = controller_transform.translation;
@@ -277,274 +961,285 @@ pub fn update_hand_bones_emulated(
     let palm = hand_transform_array[HandJoint::PALM];
     calc_transforms[HandJoint::PALM] = Transform {
         translation: hand_translation + palm.translation,
+        rotation: palm_quat,
         ..default()
     };
     //wrist
     let wrist = hand_transform_array[HandJoint::WRIST];
     calc_transforms[HandJoint::WRIST] = Transform {
         translation: hand_translation + palm.translation + palm_quat.mul_vec3(wrist.translation),
+        rotation: palm_quat,
         ..default()
     };
+    let finger_start = hand_translation
+        + palm_quat.mul_vec3(palm.translation)
+        + palm_quat.mul_vec3(wrist.translation);
 
-    //thumb
-    let thumb_joints = [
-        HandJoint::THUMB_METACARPAL,
-        HandJoint::THUMB_PROXIMAL,
-        HandJoint::THUMB_DISTAL,
-        HandJoint::THUMB_TIP,
-    ];
-    let mut prior_start: Option<Vec3> = None;
-    let mut prior_quat: Option<Quat> = None;
-    let mut prior_vector: Option<Vec3> = None;
-    let splay = Quat::from_rotation_y(splay_direction * 30.0 * PI / 180.0);
+    //thumb, curls sideways towards the palm
+    let splay = Quat::from_rotation_y(
+        splay_direction * (splay_config.thumb + pose.thumb_splay) * PI / 180.0,
+    );
     let huh = Quat::from_rotation_x(-35.0 * PI / 180.0);
-    let splay_quat = palm_quat.mul_quat(huh).mul_quat(splay);
-    for bone in thumb_joints.iter() {
-        match prior_start {
-            Some(start) => {
-                let curl_angle: f32 = get_bone_curl_angle(*bone, thumb_curl);
-                let tp_lrot = Quat::from_rotation_y(splay_direction * curl_angle * PI / 180.0);
-                let tp_quat = prior_quat.unwrap().mul_quat(tp_lrot);
-                let thumb_prox = hand_transform_array[*bone];
-                let tp_start = start + prior_vector.unwrap();
-                let tp_vector = tp_quat.mul_vec3(thumb_prox.translation);
-                prior_start = Some(tp_start);
-                prior_quat = Some(tp_quat);
-                prior_vector = Some(tp_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tp_start + tp_vector,
-                    ..default()
-                };
-            }
-            None => {
-                let thumb_meta = hand_transform_array[*bone];
-                let tm_start = hand_translation
-                    + palm_quat.mul_vec3(palm.translation)
-                    + palm_quat.mul_vec3(wrist.translation);
-                let tm_vector = palm_quat.mul_vec3(thumb_meta.translation);
-                prior_start = Some(tm_start);
-                prior_quat = Some(splay_quat);
-                prior_vector = Some(tm_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tm_start + tm_vector,
-                    ..default()
-                };
-            }
+    // mirrored for the left hand like the open hand pose
+    let (offset_translation, offset_rotation) = match hand {
+        Hand::Left => {
+            let t = pose.thumb_offset.translation;
+            let r = pose.thumb_offset.rotation;
+            (
+                Vec3::new(-t.x, t.y, t.z),
+                Quat::from_xyzw(r.x, -r.y, -r.z, r.w),
+            )
         }
-    }
-
-    //index
-    let thumb_joints = [
-        HandJoint::INDEX_METACARPAL,
-        HandJoint::INDEX_PROXIMAL,
-        HandJoint::INDEX_INTERMEDIATE,
-        HandJoint::INDEX_DISTAL,
-        HandJoint::INDEX_TIP,
-    ];
-    let mut prior_start: Option<Vec3> = None;
-    let mut prior_quat: Option<Quat> = None;
-    let mut prior_vector: Option<Vec3> = None;
-    let splay = Quat::from_rotation_y(splay_direction * 10.0 * PI / 180.0);
-    let splay_quat = palm_quat.mul_quat(splay);
-    for bone in thumb_joints.iter() {
-        match prior_start {
-            Some(start) => {
-                let curl_angle: f32 = get_bone_curl_angle(*bone, index_curl);
-                let tp_lrot = Quat::from_rotation_x(curl_angle * PI / 180.0);
-                let tp_quat = prior_quat.unwrap().mul_quat(tp_lrot);
-                let thumb_prox = hand_transform_array[*bone];
-                let tp_start = start + prior_vector.unwrap();
-                let tp_vector = tp_quat.mul_vec3(thumb_prox.translation);
-                prior_start = Some(tp_start);
-                prior_quat = Some(tp_quat);
-                prior_vector = Some(tp_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tp_start + tp_vector,
-                    ..default()
-                };
-            }
-            None => {
-                let thumb_meta = hand_transform_array[*bone];
-                let tm_start = hand_translation
-                    + palm_quat.mul_vec3(palm.translation)
-                    + palm_quat.mul_vec3(wrist.translation);
-                let tm_vector = palm_quat.mul_vec3(thumb_meta.translation);
-                prior_start = Some(tm_start);
-                prior_quat = Some(splay_quat);
-                prior_vector = Some(tm_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tm_start + tm_vector,
-                    ..default()
-                };
-            }
-        }
-    }
+        Hand::Right => (pose.thumb_offset.translation, pose.thumb_offset.rotation),
+    };
+    let thumb_palm_quat = palm_quat.mul_quat(offset_rotation);
+    // the thumb metacarpal moves with the thumb, unlike the ones of the fingers
+    let thumb_quat = thumb_palm_quat.mul_quat(huh).mul_quat(splay);
+    emulate_finger(
+        &mut calc_transforms,
+        &hand_transform_array,
+        &[
+            HandJoint::THUMB_METACARPAL,
+            HandJoint::THUMB_PROXIMAL,
+            HandJoint::THUMB_DISTAL,
+            HandJoint::THUMB_TIP,
+        ],
+        finger_start + palm_quat.mul_vec3(offset_translation),
+        thumb_palm_quat,
+        thumb_quat,
+        thumb_quat,
+        |bone| {
+            let curl_angle = get_bone_curl_angle(bone, pose.thumb_curl, config);
+            Quat::from_rotation_y(splay_direction * curl_angle * PI / 180.0)
+        },
+    );
 
-    //middle
-    let thumb_joints = [
-        HandJoint::MIDDLE_METACARPAL,
-        HandJoint::MIDDLE_PROXIMAL,
-        HandJoint::MIDDLE_INTERMEDIATE,
-        HandJoint::MIDDLE_DISTAL,
-        HandJoint::MIDDLE_TIP,
-    ];
-    let mut prior_start: Option<Vec3> = None;
-    let mut prior_quat: Option<Quat> = None;
-    let mut prior_vector: Option<Vec3> = None;
-    let splay = Quat::from_rotation_y(splay_direction * 0.0 * PI / 180.0);
-    let splay_quat = palm_quat.mul_quat(splay);
-    for bone in thumb_joints.iter() {
-        match prior_start {
-            Some(start) => {
-                let curl_angle: f32 = get_bone_curl_angle(*bone, middle_curl);
-                let tp_lrot = Quat::from_rotation_x(curl_angle * PI / 180.0);
-                let tp_quat = prior_quat.unwrap().mul_quat(tp_lrot);
-                let thumb_prox = hand_transform_array[*bone];
-                let tp_start = start + prior_vector.unwrap();
-                let tp_vector = tp_quat.mul_vec3(thumb_prox.translation);
-                prior_start = Some(tp_start);
-                prior_quat = Some(tp_quat);
-                prior_vector = Some(tp_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tp_start + tp_vector,
-                    ..default()
-                };
-            }
-            None => {
-                let thumb_meta = hand_transform_array[*bone];
-                let tm_start = hand_translation
-                    + palm_quat.mul_vec3(palm.translation)
-                    + palm_quat.mul_vec3(wrist.translation);
-                let tm_vector = palm_quat.mul_vec3(thumb_meta.translation);
-                prior_start = Some(tm_start);
-                prior_quat = Some(splay_quat);
-                prior_vector = Some(tm_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tm_start + tm_vector,
-                    ..default()
-                };
-            }
-        }
-    }
-    //ring
-    let thumb_joints = [
-        HandJoint::RING_METACARPAL,
-        HandJoint::RING_PROXIMAL,
-        HandJoint::RING_INTERMEDIATE,
-        HandJoint::RING_DISTAL,
-        HandJoint::RING_TIP,
+    let fingers = [
+        (
+            [
+                HandJoint::INDEX_METACARPAL,
+                HandJoint::INDEX_PROXIMAL,
+                HandJoint::INDEX_INTERMEDIATE,
+                HandJoint::INDEX_DISTAL,
+                HandJoint::INDEX_TIP,
+            ],
+            splay_config.index,
+            pose.index_curl,
+        ),
+        (
+            [
+                HandJoint::MIDDLE_METACARPAL,
+                HandJoint::MIDDLE_PROXIMAL,
+                HandJoint::MIDDLE_INTERMEDIATE,
+                HandJoint::MIDDLE_DISTAL,
+                HandJoint::MIDDLE_TIP,
+            ],
+            splay_config.middle,
+            pose.middle_curl,
+        ),
+        (
+            [
+                HandJoint::RING_METACARPAL,
+                HandJoint::RING_PROXIMAL,
+                HandJoint::RING_INTERMEDIATE,
+                HandJoint::RING_DISTAL,
+                HandJoint::RING_TIP,
+            ],
+            splay_config.ring,
+            pose.ring_curl,
+        ),
+        (
+            [
+                HandJoint::LITTLE_METACARPAL,
+                HandJoint::LITTLE_PROXIMAL,
+                HandJoint::LITTLE_INTERMEDIATE,
+                HandJoint::LITTLE_DISTAL,
+                HandJoint::LITTLE_TIP,
+            ],
+            splay_config.little,
+            pose.little_curl,
+        ),
     ];
-    let mut prior_start: Option<Vec3> = None;
-    let mut prior_quat: Option<Quat> = None;
-    let mut prior_vector: Option<Vec3> = None;
-    let splay = Quat::from_rotation_y(splay_direction * -10.0 * PI / 180.0);
-    let splay_quat = palm_quat.mul_quat(splay);
-    for bone in thumb_joints.iter() {
-        match prior_start {
-            Some(start) => {
-                let curl_angle: f32 = get_bone_curl_angle(*bone, ring_curl);
-                let tp_lrot = Quat::from_rotation_x(curl_angle * PI / 180.0);
-                let tp_quat = prior_quat.unwrap().mul_quat(tp_lrot);
-                let thumb_prox = hand_transform_array[*bone];
-                let tp_start = start + prior_vector.unwrap();
-                let tp_vector = tp_quat.mul_vec3(thumb_prox.translation);
-                prior_start = Some(tp_start);
-                prior_quat = Some(tp_quat);
-                prior_vector = Some(tp_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tp_start + tp_vector,
-                    ..default()
-                };
-            }
-            None => {
-                let thumb_meta = hand_transform_array[*bone];
-                let tm_start = hand_translation
-                    + palm_quat.mul_vec3(palm.translation)
-                    + palm_quat.mul_vec3(wrist.translation);
-                let tm_vector = palm_quat.mul_vec3(thumb_meta.translation);
-                prior_start = Some(tm_start);
-                prior_quat = Some(splay_quat);
-                prior_vector = Some(tm_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tm_start + tm_vector,
-                    ..default()
-                };
-            }
-        }
+    for (joints, splay, curl) in fingers {
+        let splay = Quat::from_rotation_y(splay_direction * splay * PI / 180.0);
+        // the metacarpals fan out from the wrist so the knuckles are spread across the palm
+        // twice as far as the metacarpal joints, no matter how far the fingers are splayed
+        let metacarpal = hand_transform_array[joints[0]].translation;
+        let metacarpal_length = hand_transform_array[joints[1]].translation.length();
+        let fan = Quat::from_rotation_y((-metacarpal.x / metacarpal_length).asin());
+        emulate_finger(
+            &mut calc_transforms,
+            &hand_transform_array,
+            &joints,
+            finger_start,
+            palm_quat,
+            palm_quat.mul_quat(fan),
+            palm_quat.mul_quat(splay),
+            |bone| Quat::from_rotation_x(get_bone_curl_angle(bone, curl, config) * PI / 180.0),
+        );
     }
+    calc_transforms
+}
 
-    //little
-    let thumb_joints = [
-        HandJoint::LITTLE_METACARPAL,
-        HandJoint::LITTLE_PROXIMAL,
-        HandJoint::LITTLE_INTERMEDIATE,
-        HandJoint::LITTLE_DISTAL,
-        HandJoint::LITTLE_TIP,
-    ];
-    let mut prior_start: Option<Vec3> = None;
-    let mut prior_quat: Option<Quat> = None;
-    let mut prior_vector: Option<Vec3> = None;
-    let splay = Quat::from_rotation_y(splay_direction * -20.0 * PI / 180.0);
-    let splay_quat = palm_quat.mul_quat(splay);
-    for bone in thumb_joints.iter() {
-        match prior_start {
-            Some(start) => {
-                let curl_angle: f32 = get_bone_curl_angle(*bone, little_curl);
-                let tp_lrot = Quat::from_rotation_x(curl_angle * PI / 180.0);
-                let tp_quat = prior_quat.unwrap().mul_quat(tp_lrot);
-                let thumb_prox = hand_transform_array[*bone];
-                let tp_start = start + prior_vector.unwrap();
-                let tp_vector = tp_quat.mul_vec3(thumb_prox.translation);
-                prior_start = Some(tp_start);
-                prior_quat = Some(tp_quat);
-                prior_vector = Some(tp_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tp_start + tp_vector,
-                    ..default()
-                };
-            }
-            None => {
-                let thumb_meta = hand_transform_array[*bone];
-                let tm_start = hand_translation
-                    + palm_quat.mul_vec3(palm.translation)
-                    + palm_quat.mul_vec3(wrist.translation);
-                let tm_vector = palm_quat.mul_vec3(thumb_meta.translation);
-                prior_start = Some(tm_start);
-                prior_quat = Some(splay_quat);
-                prior_vector = Some(tm_vector);
-                //store it
-                calc_transforms[*bone] = Transform {
-                    translation: tm_start + tm_vector,
-                    ..default()
-                };
-            }
-        }
+// walks a finger from its metacarpal to the tip, every joint after the metacarpal
+// adds its curl to the rotation of the joints before it. the metacarpal bone up to the
+// proximal joint is rotated by `metacarpal_quat`, the bones after it by `splay_quat`
+#[allow(clippy::too_many_arguments)]
+fn emulate_finger(
+    calc_transforms: &mut [Transform; 26],
+    hand_transform_array: &[Transform; 26],
+    joints: &[HandJoint],
+    start: Vec3,
+    palm_quat: Quat,
+    metacarpal_quat: Quat,
+    splay_quat: Quat,
+    curl_rotation: impl Fn(HandJoint) -> Quat,
+) {
+    let (metacarpal, rest) = joints.split_first().unwrap();
+    let (proximal, rest) = rest.split_first().unwrap();
+    // like tracked joints, every joint is oriented along the bone that starts at it
+    let proximal_curl = curl_rotation(*proximal);
+    let metacarpal_rotation = metacarpal_quat.mul_quat(proximal_curl);
+    let mut position = start + palm_quat.mul_vec3(hand_transform_array[*metacarpal].translation);
+    calc_transforms[*metacarpal] = Transform {
+        translation: position,
+        rotation: metacarpal_rotation,
+        ..default()
+    };
+    position += metacarpal_rotation.mul_vec3(hand_transform_array[*proximal].translation);
+    let mut rotation = splay_quat.mul_quat(proximal_curl);
+    let mut joint = *proximal;
+    for bone in rest {
+        rotation = rotation.mul_quat(curl_rotation(*bone));
+        calc_transforms[joint] = Transform {
+            translation: position,
+            rotation,
+            ..default()
+        };
+        position += rotation.mul_vec3(hand_transform_array[*bone].translation);
+        joint = *bone;
     }
-    calc_transforms
+    // the tip keeps the rotation of the last bone
+    calc_transforms[joint] = Transform {
+        translation: position,
+        rotation,
+        ..default()
+    };
 }
 
-fn get_bone_curl_angle(bone: HandJoint, curl: f32) -> f32 {
+fn get_bone_curl_angle(bone: HandJoint, curl: f32, config: &HandEmulationConfig) -> f32 {
     let mul: f32 = match bone {
         HandJoint::INDEX_PROXIMAL => 0.0,
         HandJoint::MIDDLE_PROXIMAL => 0.0,
         HandJoint::RING_PROXIMAL => 0.0,
         HandJoint::LITTLE_PROXIMAL => 0.0,
         HandJoint::THUMB_PROXIMAL => 0.0,
-        HandJoint::THUMB_TIP => 0.1,
-        HandJoint::THUMB_DISTAL => 0.1,
-        HandJoint::THUMB_METACARPAL => 0.1,
+        HandJoint::THUMB_TIP => config.thumb_multiplier,
+        HandJoint::THUMB_DISTAL => config.thumb_multiplier,
+        HandJoint::THUMB_METACARPAL => config.thumb_multiplier,
+        HandJoint::INDEX_METACARPAL
+        | HandJoint::INDEX_INTERMEDIATE
+        | HandJoint::INDEX_DISTAL
+        | HandJoint::INDEX_TIP => config.index_multiplier,
+        HandJoint::MIDDLE_METACARPAL
+        | HandJoint::MIDDLE_INTERMEDIATE
+        | HandJoint::MIDDLE_DISTAL
+        | HandJoint::MIDDLE_TIP => config.middle_multiplier,
+        HandJoint::RING_METACARPAL
+        | HandJoint::RING_INTERMEDIATE
+        | HandJoint::RING_DISTAL
+        | HandJoint::RING_TIP => config.ring_multiplier,
+        HandJoint::LITTLE_METACARPAL
+        | HandJoint::LITTLE_INTERMEDIATE
+        | HandJoint::LITTLE_DISTAL
+        | HandJoint::LITTLE_TIP => config.little_multiplier,
         _ => 1.0,
     };
-    let curl_angle = -((mul * curl * 80.0) + 5.0);
+    let curl_angle = -((mul * curl * config.max_curl_degrees) + config.base_curl_degrees);
     #[allow(clippy::needless_return)]
     return curl_angle;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pose_hand(controller: Transform, hand: Hand, curl: f32) -> [Transform; 26] {
+        let pose = EmulatedHandPoseData {
+            index_curl: curl,
+            middle_curl: curl,
+            ring_curl: curl,
+            little_curl: curl,
+            ..default()
+        };
+        update_hand_bones_emulated(
+            &controller,
+            hand,
+            &pose,
+            &HandEmulationConfig::default(),
+            &HandSplayConfig::default(),
+        )
+    }
+
+    const FINGERS: [(HandJoint, HandJoint); 4] = [
+        (HandJoint::INDEX_PROXIMAL, HandJoint::INDEX_TIP),
+        (HandJoint::MIDDLE_PROXIMAL, HandJoint::MIDDLE_TIP),
+        (HandJoint::RING_PROXIMAL, HandJoint::RING_TIP),
+        (HandJoint::LITTLE_PROXIMAL, HandJoint::LITTLE_TIP),
+    ];
+
+    #[test]
+    fn curled_index_tip_is_near_the_palm() {
+        let open = pose_hand(Transform::IDENTITY, Hand::Right, 0.0);
+        let curled = pose_hand(Transform::IDENTITY, Hand::Right, 1.0);
+        let palm = curled[HandJoint::PALM].translation;
+        let open_distance = open[HandJoint::INDEX_TIP].translation.distance(palm);
+        let curled_distance = curled[HandJoint::INDEX_TIP].translation.distance(palm);
+        assert!(curled_distance < 0.05, "{}", curled_distance);
+        assert!(curled_distance < open_distance * 0.5);
+    }
+
+    #[test]
+    fn open_finger_tips_are_in_front_of_the_knuckles() {
+        for hand in [Hand::Left, Hand::Right] {
+            let bones = pose_hand(Transform::IDENTITY, hand, 0.0);
+            let forward = bones[HandJoint::PALM].rotation * Vec3::NEG_Z;
+            for (knuckle, tip) in FINGERS {
+                let along = (bones[tip].translation - bones[knuckle].translation).dot(forward);
+                assert!(along > 0.03, "{:?} {:?}: {}", hand, tip, along);
+            }
+        }
+    }
+
+    #[test]
+    fn knuckles_are_spread_across_the_palm() {
+        let bones = pose_hand(Transform::IDENTITY, Hand::Right, 0.0);
+        for (a, b) in FINGERS.iter().zip(FINGERS.iter().skip(1)) {
+            let distance = bones[a.0].translation.distance(bones[b.0].translation);
+            assert!(distance > 0.01, "{:?} {:?}: {}", a.0, b.0, distance);
+        }
+    }
+
+    #[test]
+    fn left_hand_mirrors_the_right_hand() {
+        let left = pose_hand(Transform::IDENTITY, Hand::Left, 0.5);
+        let right = pose_hand(Transform::IDENTITY, Hand::Right, 0.5);
+        for (left, right) in left.iter().zip(right.iter()) {
+            let mirrored = right.translation * Vec3::new(-1.0, 1.0, 1.0);
+            assert!(left.translation.distance(mirrored) < 1e-5);
+        }
+    }
+
+    #[test]
+    fn bones_follow_the_controller() {
+        let controller = Transform::from_xyz(0.3, 1.2, -0.5);
+        let at_origin = pose_hand(Transform::IDENTITY, Hand::Right, 0.5);
+        let moved = pose_hand(controller, Hand::Right, 0.5);
+        for (at_origin, moved) in at_origin.iter().zip(moved.iter()) {
+            let offset = moved.translation - at_origin.translation;
+            assert!(offset.distance(controller.translation) < 1e-5);
+            assert!(moved.rotation.angle_between(at_origin.rotation) < 1e-4);
+        }
+        assert_eq!(moved, pose_hand(controller, Hand::Right, 0.5));
+    }
+}
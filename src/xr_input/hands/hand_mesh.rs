@@ -0,0 +1,176 @@
+use bevy::{asset::LoadState, prelude::*};
+
+use crate::xr_input::Hand;
+
+use super::{
+    common::{HandBoneRadius, HandGizmoConfig},
+    custom_skeleton::{CustomHandSkeleton, CustomHandSkeletonMapped, HandBoneNames},
+    HandBone,
+};
+
+/// Shows rigged glTF hand meshes driven by the hand bones, falls back to drawing
+/// the bones with gizmos for a hand whose mesh doesn't load or has none of the joints
+#[derive(Default)]
+pub struct HandMeshPlugin;
+
+impl Plugin for HandMeshPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HandMeshFallback>();
+        app.init_resource::<HandGizmoConfig>();
+        app.add_systems(Update, spawn_hand_meshes);
+        app.add_systems(
+            PostUpdate,
+            (validate_hand_meshes, draw_fallback_hands).chain(),
+        );
+    }
+}
+
+/// Which glTF files to load as hands, e.g. `models/left_hand.glb`, the first scene is used.
+/// Changing it respawns the meshes.
+#[derive(Resource, Clone, Debug)]
+pub struct HandMeshConfig {
+    pub left_gltf: Option<String>,
+    pub right_gltf: Option<String>,
+    /// joint names of both meshes, see [`CustomHandSkeleton`]
+    pub joint_name_map: HandBoneNames,
+    /// see [`CustomHandSkeleton::rotation_offset`]
+    pub rotation_offset: Quat,
+}
+
+impl Default for HandMeshConfig {
+    fn default() -> Self {
+        Self {
+            left_gltf: None,
+            right_gltf: None,
+            joint_name_map: HandBoneNames::default(),
+            rotation_offset: Quat::IDENTITY,
+        }
+    }
+}
+
+impl HandMeshConfig {
+    pub fn get(&self, hand: Hand) -> Option<&str> {
+        match hand {
+            Hand::Left => self.left_gltf.as_deref(),
+            Hand::Right => self.right_gltf.as_deref(),
+        }
+    }
+}
+
+/// The root of a hand mesh spawned from [`HandMeshConfig`]
+#[derive(Component, Clone, Copy, Debug)]
+pub struct HandMesh {
+    pub hand: Hand,
+}
+
+/// Hands whose mesh failed, these are drawn with gizmos instead
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HandMeshFallback {
+    pub left: bool,
+    pub right: bool,
+}
+
+impl HandMeshFallback {
+    pub fn get(&self, hand: Hand) -> bool {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+    fn set(&mut self, hand: Hand, fallback: bool) {
+        match hand {
+            Hand::Left => self.left = fallback,
+            Hand::Right => self.right = fallback,
+        }
+    }
+}
+
+fn spawn_hand_meshes(
+    mut commands: Commands,
+    config: Option<Res<HandMeshConfig>>,
+    asset_server: Res<AssetServer>,
+    mut fallback: ResMut<HandMeshFallback>,
+    meshes: Query<Entity, With<HandMesh>>,
+) {
+    let Some(config) = config else {
+        return;
+    };
+    if !config.is_changed() {
+        return;
+    }
+    for entity in meshes.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    *fallback = HandMeshFallback::default();
+    for hand in [Hand::Left, Hand::Right] {
+        let Some(path) = config.get(hand) else {
+            continue;
+        };
+        let mut skeleton = CustomHandSkeleton::new(hand, config.joint_name_map.clone());
+        skeleton.rotation_offset = config.rotation_offset;
+        commands.spawn((
+            SceneBundle {
+                scene: asset_server.load(format!("{}#Scene0", path)),
+                ..default()
+            },
+            skeleton,
+            HandMesh { hand },
+        ));
+    }
+}
+
+/// a mesh has failed once its file failed to load, or once its scene spawned
+/// without any joint being mapped, mapping already warned about every missing joint
+fn validate_hand_meshes(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut fallback: ResMut<HandMeshFallback>,
+    meshes: Query<
+        (Entity, &HandMesh, &Handle<Scene>, Has<Children>),
+        Without<CustomHandSkeletonMapped>,
+    >,
+) {
+    for (entity, mesh, scene, spawned) in meshes.iter() {
+        let failed = match asset_server.load_state(scene.id()) {
+            LoadState::Failed => {
+                warn!("unable to load the {:?} hand mesh", mesh.hand);
+                true
+            }
+            LoadState::Loaded if spawned => {
+                warn!(
+                    "none of the joints of the {:?} hand mesh were found",
+                    mesh.hand
+                );
+                true
+            }
+            _ => false,
+        };
+        if failed {
+            info!("drawing the {:?} hand with gizmos instead", mesh.hand);
+            fallback.set(mesh.hand, true);
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn draw_fallback_hands(
+    mut gizmos: Gizmos,
+    fallback: Res<HandMeshFallback>,
+    config: Res<HandGizmoConfig>,
+    bones: Query<(&Transform, &Hand, &HandBone, &HandBoneRadius)>,
+) {
+    if *fallback == HandMeshFallback::default() {
+        return;
+    }
+    for (transform, hand, bone, radius) in bones.iter() {
+        if !fallback.get(*hand) {
+            continue;
+        }
+        gizmos.sphere(
+            transform.translation,
+            transform.rotation,
+            radius.0 * config.radius_multiplier,
+            config.color(bone),
+        );
+    }
+}
@@ -1,15 +1,22 @@
+use std::ptr;
+
 use bevy::prelude::*;
-use openxr::{HandTracker, Result, SpaceLocationFlags};
+use openxr::{HandTracker, Result, SpaceLocationFlags, SpaceVelocityFlags};
 
-use super::common::HandBoneRadius;
+use super::common::{HandBoneConfidence, HandBoneRadius};
 use crate::{
     input::XrInput,
-    resources::{XrFrameState, XrSession},
+    resources::{XrFrameState, XrInstance, XrSession},
     xr_init::xr_only,
-    xr_input::{hands::HandBone, trackers::OpenXRTrackingRoot, Hand, QuatConv, Vec3Conv},
+    xr_input::{
+        hands::HandBone,
+        smoothing::{smooth_transform, smoothing_alpha, HandSmoothing},
+        trackers::{AngularVelocity, LinearVelocity, OpenXRTrackingRoot},
+        Hand, QuatConv, Vec3Conv,
+    },
 };
 
-use super::BoneTrackingStatus;
+use super::{BoneTrackingStatus, HandTrackingSourceChanged, HandTrackingSources};
 
 #[derive(Resource, PartialEq)]
 pub enum DisableHandTracking {
@@ -19,10 +26,109 @@ pub enum DisableHandTracking {
 }
 pub struct HandTrackingPlugin;
 
+/// Which joints the hand trackers report, defaults to the standard 26 joints
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HandJointSet {
+    #[default]
+    Default,
+    /// the standard joints plus the elbow as a [`HandForearm`] entity for each hand,
+    /// needs XR_ULTRALEAP_hand_tracking_forearm, otherwise only the standard joints are tracked
+    WithForearm,
+}
+
+/// The elbow of a hand, only spawned with [`HandJointSet::WithForearm`]
+#[derive(Component, Clone, Copy, Debug)]
+pub struct HandForearm;
+
+// XR_HAND_FOREARM_JOINT_ELBOW_ULTRALEAP, the joints before it are the standard ones
+const ELBOW_JOINT_INDEX: usize = 26;
+const FOREARM_JOINT_COUNT: usize = 27;
+
+// openxr doesn't wrap other joint sets, so these trackers are created and located raw.
+// they only report the elbow, the standard trackers keep driving the hand bones
+struct ForearmTracker {
+    handle: openxr::sys::HandTrackerEXT,
+    locate: openxr::sys::pfn::LocateHandJointsEXT,
+    destroy: openxr::sys::pfn::DestroyHandTrackerEXT,
+}
+
+impl ForearmTracker {
+    fn new(instance: &XrInstance, session: &XrSession, hand: openxr::HandEXT) -> Result<Self> {
+        let Some(fp) = instance.exts().ext_hand_tracking.as_ref() else {
+            return Err(openxr::sys::Result::ERROR_EXTENSION_NOT_PRESENT);
+        };
+        let info = openxr::sys::HandTrackerCreateInfoEXT {
+            ty: openxr::sys::HandTrackerCreateInfoEXT::TYPE,
+            next: ptr::null(),
+            hand,
+            hand_joint_set: openxr::HandJointSetEXT::HAND_WITH_FOREARM_ULTRALEAP,
+        };
+        let mut handle = openxr::sys::HandTrackerEXT::NULL;
+        let result = unsafe { (fp.create_hand_tracker)(session.as_raw(), &info, &mut handle) };
+        if result.into_raw() < 0 {
+            return Err(result);
+        }
+        Ok(Self {
+            handle,
+            locate: fp.locate_hand_joints,
+            destroy: fp.destroy_hand_tracker,
+        })
+    }
+
+    fn locate_elbow(&self, space: &openxr::Space, time: openxr::Time) -> Option<HandJoint> {
+        let info = openxr::sys::HandJointsLocateInfoEXT {
+            ty: openxr::sys::HandJointsLocateInfoEXT::TYPE,
+            next: ptr::null(),
+            base_space: space.as_raw(),
+            time,
+        };
+        let mut joints: [openxr::sys::HandJointLocationEXT; FOREARM_JOINT_COUNT] =
+            unsafe { std::mem::zeroed() };
+        let mut locations = openxr::sys::HandJointLocationsEXT {
+            ty: openxr::sys::HandJointLocationsEXT::TYPE,
+            next: ptr::null_mut(),
+            is_active: false.into(),
+            joint_count: FOREARM_JOINT_COUNT as u32,
+            joint_locations: joints.as_mut_ptr(),
+        };
+        let result = unsafe { (self.locate)(self.handle, &info, &mut locations) };
+        if result.into_raw() < 0 || !bool::from(locations.is_active) {
+            return None;
+        }
+        let elbow = joints[ELBOW_JOINT_INDEX];
+        Some(HandJoint {
+            position: elbow.pose.position.to_vec3(),
+            position_valid: elbow
+                .location_flags
+                .contains(SpaceLocationFlags::POSITION_VALID),
+            position_tracked: elbow
+                .location_flags
+                .contains(SpaceLocationFlags::POSITION_TRACKED),
+            orientation: elbow.pose.orientation.to_quat(),
+            orientation_valid: elbow
+                .location_flags
+                .contains(SpaceLocationFlags::ORIENTATION_VALID),
+            orientation_tracked: elbow
+                .location_flags
+                .contains(SpaceLocationFlags::ORIENTATION_TRACKED),
+            radius: elbow.radius,
+        })
+    }
+}
+
+impl Drop for ForearmTracker {
+    fn drop(&mut self) {
+        unsafe {
+            (self.destroy)(self.handle);
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct HandTrackingData {
     left_hand: HandTracker,
     right_hand: HandTracker,
+    forearms: Option<[ForearmTracker; 2]>,
 }
 
 impl HandTrackingData {
@@ -32,8 +138,42 @@ impl HandTrackingData {
         Ok(HandTrackingData {
             left_hand: left,
             right_hand: right,
+            forearms: None,
         })
     }
+
+    /// like [`HandTrackingData::new`], but also tracks the forearms if `joint_set` asks for them
+    /// and the runtime supports it
+    pub fn with_joint_set(
+        instance: &XrInstance,
+        session: &XrSession,
+        joint_set: HandJointSet,
+    ) -> Result<HandTrackingData> {
+        let mut data = Self::new(session)?;
+        if joint_set != HandJointSet::WithForearm {
+            return Ok(data);
+        }
+        if instance.exts().ultraleap_hand_tracking_forearm.is_none() {
+            warn!("XR_ULTRALEAP_hand_tracking_forearm is not enabled, not tracking the forearms");
+            return Ok(data);
+        }
+        let trackers =
+            ForearmTracker::new(instance, session, openxr::HandEXT::LEFT).and_then(|left| {
+                Ok([
+                    left,
+                    ForearmTracker::new(instance, session, openxr::HandEXT::RIGHT)?,
+                ])
+            });
+        match trackers {
+            Ok(trackers) => data.forearms = Some(trackers),
+            Err(err) => warn!("unable to track the forearms: {}", err),
+        }
+        Ok(data)
+    }
+
+    pub fn tracks_forearms(&self) -> bool {
+        self.forearms.is_some()
+    }
     pub fn get_ref<'a>(
         &'a self,
         input: &'a XrInput,
@@ -116,10 +256,36 @@ impl<'a> HandTrackingRef<'a> {
             })
             .map(|joints| HandJoints { inner: joints })
     }
+
+    /// linear and angular velocity of every joint in the stage, `None` for invalid ones
+    pub fn get_velocities(&self, side: Hand) -> Option<[Option<(Vec3, Vec3)>; 26]> {
+        let (_, velocities) = self
+            .input
+            .stage
+            .relate_hand_joints(
+                match side {
+                    Hand::Left => &self.tracking.left_hand,
+                    Hand::Right => &self.tracking.right_hand,
+                },
+                self.frame_state.lock().unwrap().predicted_display_time,
+            )
+            .ok()??;
+        let valid = SpaceVelocityFlags::LINEAR_VALID | SpaceVelocityFlags::ANGULAR_VALID;
+        Some(velocities.map(|joint| {
+            joint.velocity_flags.contains(valid).then(|| {
+                (
+                    joint.linear_velocity.to_vec3(),
+                    joint.angular_velocity.to_vec3(),
+                )
+            })
+        }))
+    }
 }
 
 impl Plugin for HandTrackingPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<HandTrackingSources>();
+        app.add_event::<HandTrackingSourceChanged>();
         app.add_systems(
             PreUpdate,
             (
@@ -129,15 +295,111 @@ impl Plugin for HandTrackingPlugin {
                     })
                     .run_if(xr_only()),
                 update_tracking_state_on_disable,
+                update_forearms.run_if(xr_only()),
+                update_hand_bone_velocities.run_if(xr_only()),
             ),
         );
     }
 }
 
+/// only relates the joints if a bone has a [`LinearVelocity`] or [`AngularVelocity`]
+#[allow(clippy::type_complexity)]
+pub fn update_hand_bone_velocities(
+    hand_tracking: Option<Res<HandTrackingData>>,
+    xr_input: Res<XrInput>,
+    xr_frame_state: Res<XrFrameState>,
+    root_query: Query<&Transform, With<OpenXRTrackingRoot>>,
+    mut bones: Query<
+        (
+            &Hand,
+            &HandBone,
+            &BoneTrackingStatus,
+            Option<&mut LinearVelocity>,
+            Option<&mut AngularVelocity>,
+        ),
+        Or<(With<LinearVelocity>, With<AngularVelocity>)>,
+    >,
+) {
+    let Some(hand_tracking) = hand_tracking else {
+        return;
+    };
+    if bones.is_empty() {
+        return;
+    }
+    let hand_ref = hand_tracking.get_ref(&xr_input, &xr_frame_state);
+    let left = hand_ref.get_velocities(Hand::Left);
+    let right = hand_ref.get_velocities(Hand::Right);
+    // the bones are in world space, so are their velocities
+    let root_rotation = root_query
+        .get_single()
+        .map_or(Quat::IDENTITY, |t| t.rotation);
+    for (hand, bone, status, linear, angular) in bones.iter_mut() {
+        if *status != BoneTrackingStatus::Tracked {
+            continue;
+        }
+        let velocities = match hand {
+            Hand::Left => left.as_ref(),
+            Hand::Right => right.as_ref(),
+        };
+        let Some((linear_velocity, angular_velocity)) =
+            velocities.and_then(|v| v[bone.get_index_from_bone()])
+        else {
+            continue;
+        };
+        if let Some(mut linear) = linear {
+            linear.0 = root_rotation * linear_velocity;
+        }
+        if let Some(mut angular) = angular {
+            angular.0 = root_rotation * angular_velocity;
+        }
+    }
+}
+
+/// spawns the [`HandForearm`] entities once the forearms are tracked and moves them to the elbows
+pub fn update_forearms(
+    mut commands: Commands,
+    hand_tracking: Option<Res<HandTrackingData>>,
+    xr_input: Res<XrInput>,
+    xr_frame_state: Res<XrFrameState>,
+    root_query: Query<&Transform, (With<OpenXRTrackingRoot>, Without<HandForearm>)>,
+    mut forearms: Query<(&mut Transform, &Hand, &mut BoneTrackingStatus), With<HandForearm>>,
+) {
+    let Some(trackers) = hand_tracking.as_ref().and_then(|h| h.forearms.as_ref()) else {
+        return;
+    };
+    if forearms.is_empty() {
+        for hand in [Hand::Left, Hand::Right] {
+            commands.spawn((
+                SpatialBundle::default(),
+                HandForearm,
+                hand,
+                BoneTrackingStatus::Emulated,
+            ));
+        }
+        return;
+    }
+    let root_transform = root_query.get_single().copied().unwrap_or_default();
+    let time = xr_frame_state.lock().unwrap().predicted_display_time;
+    for (mut transform, hand, mut status) in forearms.iter_mut() {
+        let tracker = &trackers[*hand as usize];
+        match tracker.locate_elbow(&xr_input.stage, time) {
+            Some(elbow) if elbow.position_valid && elbow.orientation_valid => {
+                *status = BoneTrackingStatus::Tracked;
+                *transform = transform
+                    .with_translation(root_transform.transform_point(elbow.position))
+                    .with_rotation(root_transform.rotation * elbow.orientation);
+            }
+            _ => *status = BoneTrackingStatus::Emulated,
+        }
+    }
+}
+
 fn update_tracking_state_on_disable(
     mut is_off: Local<bool>,
     disabled_tracking: Option<Res<DisableHandTracking>>,
     mut tracking_states: Query<&mut BoneTrackingStatus>,
+    mut sources: ResMut<HandTrackingSources>,
+    mut source_changed: EventWriter<HandTrackingSourceChanged>,
 ) {
     if !*is_off
         && disabled_tracking
@@ -147,17 +409,42 @@ fn update_tracking_state_on_disable(
         tracking_states
             .par_iter_mut()
             .for_each(|mut state| *state = BoneTrackingStatus::Emulated);
+        for hand in [Hand::Left, Hand::Right] {
+            set_hand_source(
+                &mut sources,
+                &mut source_changed,
+                hand,
+                BoneTrackingStatus::Emulated,
+            );
+        }
     }
     *is_off = disabled_tracking
         .as_ref()
         .is_some_and(|t| **t == DisableHandTracking::Both);
 }
 
+fn set_hand_source(
+    sources: &mut HandTrackingSources,
+    source_changed: &mut EventWriter<HandTrackingSourceChanged>,
+    hand: Hand,
+    source: BoneTrackingStatus,
+) {
+    let current = sources.get_mut(hand);
+    if *current != source {
+        *current = source;
+        source_changed.send(HandTrackingSourceChanged { hand, source });
+    }
+}
+
 pub fn update_hand_bones(
     disabled_tracking: Option<Res<DisableHandTracking>>,
+    mut sources: ResMut<HandTrackingSources>,
+    mut source_changed: EventWriter<HandTrackingSourceChanged>,
     hand_tracking: Option<Res<HandTrackingData>>,
     xr_input: Res<XrInput>,
     xr_frame_state: Res<XrFrameState>,
+    time: Res<Time>,
+    smoothing: Option<Res<HandSmoothing>>,
     root_query: Query<(&Transform, With<OpenXRTrackingRoot>, Without<HandBone>)>,
     mut bones: Query<(
         &mut Transform,
@@ -165,6 +452,7 @@ pub fn update_hand_bones(
         &HandBone,
         &mut HandBoneRadius,
         &mut BoneTrackingStatus,
+        Option<&mut HandBoneConfidence>,
     )>,
 ) {
     let hand_ref = match hand_tracking.as_ref() {
@@ -174,37 +462,73 @@ pub fn update_hand_bones(
             return;
         }
     };
-    let (root_transform, _, _) = root_query.get_single().unwrap();
+    let root_transform = root_query
+        .get_single()
+        .map(|(t, _, _)| *t)
+        .unwrap_or_default();
     let left_hand_data = hand_ref.get_poses(Hand::Left);
     let right_hand_data = hand_ref.get_poses(Hand::Right);
-    bones
-        .par_iter_mut()
-        .for_each(|(mut transform, hand, bone, mut radius, mut status)| {
-            match (&hand, disabled_tracking.as_ref().map(|d| d.as_ref())) {
-                (Hand::Left, Some(DisableHandTracking::OnlyLeft)) => {
-                    *status = BoneTrackingStatus::Emulated;
-                    return;
-                }
-                (Hand::Right, Some(DisableHandTracking::OnlyRight)) => {
-                    *status = BoneTrackingStatus::Emulated;
-                    return;
-                }
-                _ => {}
+    // each hand is decided on its own, so holding a controller in one hand
+    // only switches that hand over to emulation
+    for (hand, data, disabled) in [
+        (Hand::Left, &left_hand_data, DisableHandTracking::OnlyLeft),
+        (
+            Hand::Right,
+            &right_hand_data,
+            DisableHandTracking::OnlyRight,
+        ),
+    ] {
+        let source = match data {
+            Some(_) if disabled_tracking.as_deref() != Some(&disabled) => {
+                BoneTrackingStatus::Tracked
             }
+            _ => BoneTrackingStatus::Emulated,
+        };
+        set_hand_source(&mut sources, &mut source_changed, hand, source);
+    }
+    let alpha = smoothing_alpha(
+        smoothing.map_or(0.0, |s| s.time_constant),
+        time.delta_seconds(),
+    );
+    bones.par_iter_mut().for_each(
+        |(mut transform, hand, bone, mut radius, mut status, confidence)| {
+            let disabled = matches!(
+                (hand, disabled_tracking.as_deref()),
+                (Hand::Left, Some(DisableHandTracking::OnlyLeft))
+                    | (Hand::Right, Some(DisableHandTracking::OnlyRight))
+            );
             let bone_data = match (hand, &left_hand_data, &right_hand_data) {
-                (Hand::Left, Some(data), _) => data.get_joint(*bone),
-                (Hand::Right, _, Some(data)) => data.get_joint(*bone),
-                _ => {
-                    *status = BoneTrackingStatus::Emulated;
-                    return;
+                _ if disabled => None,
+                (Hand::Left, Some(data), _) => Some(data.get_joint(*bone)),
+                (Hand::Right, _, Some(data)) => Some(data.get_joint(*bone)),
+                _ => None,
+            };
+            let Some(bone_data) = bone_data else {
+                *status = BoneTrackingStatus::Emulated;
+                if let Some(mut confidence) = confidence {
+                    *confidence = HandBoneConfidence::EMULATED;
                 }
+                return;
             };
+            // snap to the tracked pose instead of smoothing from the emulated one
+            let mut alpha = alpha;
             if *status == BoneTrackingStatus::Emulated {
                 *status = BoneTrackingStatus::Tracked;
+                alpha = 1.0;
+            }
+            if let Some(mut confidence) = confidence {
+                *confidence = HandBoneConfidence {
+                    position_valid: bone_data.position_valid,
+                    position_tracked: bone_data.position_tracked,
+                    orientation_valid: bone_data.orientation_valid,
+                    orientation_tracked: bone_data.orientation_tracked,
+                };
             }
             radius.0 = bone_data.radius;
-            *transform = transform
+            let target = transform
                 .with_translation(root_transform.transform_point(bone_data.position))
-                .with_rotation(root_transform.rotation * bone_data.orientation)
-        });
+                .with_rotation(root_transform.rotation * bone_data.orientation);
+            *transform = smooth_transform(&transform, target, alpha);
+        },
+    );
 }
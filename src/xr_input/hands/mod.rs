@@ -1,10 +1,14 @@
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
 use self::{emulated::HandEmulationPlugin, hand_tracking::HandTrackingPlugin};
+use super::Hand;
 
 pub mod common;
+pub mod custom_skeleton;
 pub mod emulated;
+pub mod hand_mesh;
 pub mod hand_tracking;
+pub mod pinch;
 
 pub struct XrHandPlugins;
 
@@ -17,13 +21,87 @@ impl PluginGroup for XrHandPlugins {
     }
 }
 
-#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BoneTrackingStatus {
     Emulated,
     Tracked,
 }
 
-#[derive(Component, Debug, Clone, Copy)]
+/// Where the bones of a hand currently come from, tracked per hand so one hand
+/// can be hand tracked while the other is emulated from a controller
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandTrackingSources {
+    pub left: BoneTrackingStatus,
+    pub right: BoneTrackingStatus,
+}
+
+impl Default for HandTrackingSources {
+    fn default() -> Self {
+        Self {
+            left: BoneTrackingStatus::Emulated,
+            right: BoneTrackingStatus::Emulated,
+        }
+    }
+}
+
+impl HandTrackingSources {
+    pub fn get(&self, hand: Hand) -> BoneTrackingStatus {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+    pub fn get_mut(&mut self, hand: Hand) -> &mut BoneTrackingStatus {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+}
+
+/// Sent when a hand switches between articulated tracking and emulation
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct HandTrackingSourceChanged {
+    pub hand: Hand,
+    pub source: BoneTrackingStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Finger {
+    Thumb,
+    Index,
+    Middle,
+    Ring,
+    Little,
+}
+
+impl Finger {
+    pub fn tip(&self) -> HandBone {
+        match self {
+            Finger::Thumb => HandBone::ThumbTip,
+            Finger::Index => HandBone::IndexTip,
+            Finger::Middle => HandBone::MiddleTip,
+            Finger::Ring => HandBone::RingTip,
+            Finger::Little => HandBone::LittleTip,
+        }
+    }
+}
+
+/// The fingertip of a hand from the hand bone entities, works for tracked and emulated hands.
+/// Pass the iterator of a `Query<(&Transform, &Hand, &HandBone)>`.
+pub fn fingertip_transform<'a>(
+    bones: impl IntoIterator<Item = (&'a Transform, &'a Hand, &'a HandBone)>,
+    hand: Hand,
+    finger: Finger,
+) -> Option<Transform> {
+    let tip = finger.tip();
+    bones
+        .into_iter()
+        .find(|(_, h, b)| **h == hand && **b == tip)
+        .map(|(t, _, _)| *t)
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum HandBone {
     Palm,
     Wrist,
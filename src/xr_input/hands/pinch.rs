@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+
+use crate::{
+    resources::XrSession,
+    xr_input::{actions::XrActionSets, oculus_touch::subaction_path, Hand},
+};
+
+use super::{emulated::HAND_ACTION_SET, BoneTrackingStatus, HandBone, HandTrackingSources};
+
+/// strength at which a hand counts as pinching
+pub const PINCH_THRESHOLD: f32 = 0.8;
+/// strength below which a pinching hand lets go again
+pub const PINCH_RELEASE_THRESHOLD: f32 = 0.6;
+/// distance between the thumb and index tips at which a tracked hand is fully pinched
+pub const PINCH_CLOSED_DISTANCE: f32 = 0.01;
+/// distance between the thumb and index tips at which a tracked hand is fully open
+pub const PINCH_OPEN_DISTANCE: f32 = 0.06;
+
+/// Distances and thresholds of pinch detection, the gap between `threshold` and
+/// `release_threshold` keeps a noisy pinch from toggling every frame
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct PinchConfig {
+    pub closed_distance: f32,
+    pub open_distance: f32,
+    pub threshold: f32,
+    pub release_threshold: f32,
+}
+
+impl Default for PinchConfig {
+    fn default() -> Self {
+        Self {
+            closed_distance: PINCH_CLOSED_DISTANCE,
+            open_distance: PINCH_OPEN_DISTANCE,
+            threshold: PINCH_THRESHOLD,
+            release_threshold: PINCH_RELEASE_THRESHOLD,
+        }
+    }
+}
+
+/// How far each hand is pinched, from 0.0 open to 1.0 pinched, the same for every input source:
+/// - tracked hands use the distance between the thumb and index tips,
+///   between [`PinchConfig::open_distance`] and [`PinchConfig::closed_distance`]
+/// - hands emulated from a controller use the trigger value, halved while the thumb
+///   doesn't touch anything as there is no pinch without the thumb
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PinchStrength {
+    pub left: f32,
+    pub right: f32,
+    pinching: [bool; 2],
+}
+
+impl PinchStrength {
+    pub fn get(&self, hand: Hand) -> f32 {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+    fn get_mut(&mut self, hand: Hand) -> &mut f32 {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+
+    /// whether the hand is pinching, with the hysteresis of [`PinchConfig`]
+    pub fn is_pinching(&self, hand: Hand) -> bool {
+        self.pinching[hand as usize]
+    }
+}
+
+/// Sent when a hand starts or stops pinching, no matter if it is tracked or emulated
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct PinchEvent {
+    pub hand: Hand,
+    pub pinching: bool,
+    pub strength: f32,
+}
+
+pub fn pinch_strength_from_tips(thumb_tip: Vec3, index_tip: Vec3, config: &PinchConfig) -> f32 {
+    let distance = thumb_tip.distance(index_tip);
+    1.0 - ((distance - config.closed_distance) / (config.open_distance - config.closed_distance))
+        .clamp(0.0, 1.0)
+}
+
+pub fn pinch_strength_from_controller(index_value: f32, thumb_touch: bool) -> f32 {
+    match thumb_touch {
+        true => index_value,
+        false => index_value * 0.5,
+    }
+}
+
+pub fn update_pinch_strength(
+    session: Res<XrSession>,
+    action_sets: Res<XrActionSets>,
+    sources: Option<Res<HandTrackingSources>>,
+    bones: Query<(&Transform, &Hand, &HandBone)>,
+    config: Res<PinchConfig>,
+    mut strength: ResMut<PinchStrength>,
+    mut pinch_events: EventWriter<PinchEvent>,
+) {
+    for hand in [Hand::Left, Hand::Right] {
+        let source = sources
+            .as_ref()
+            .map_or(BoneTrackingStatus::Emulated, |s| s.get(hand));
+        let new_strength = match source {
+            BoneTrackingStatus::Tracked => {
+                let tip = |tip: HandBone| {
+                    bones
+                        .iter()
+                        .find(|(_, h, b)| **h == hand && **b == tip)
+                        .map(|(t, _, _)| t.translation)
+                };
+                match (tip(HandBone::ThumbTip), tip(HandBone::IndexTip)) {
+                    (Some(thumb), Some(index)) => pinch_strength_from_tips(thumb, index, &config),
+                    _ => 0.0,
+                }
+            }
+            BoneTrackingStatus::Emulated => {
+                let path = subaction_path(hand);
+                let index_value = action_sets
+                    .get_action_f32(HAND_ACTION_SET, "index_value")
+                    .ok()
+                    .and_then(|a| a.state(&session, path).ok())
+                    .map_or(0.0, |s| s.current_state);
+                let thumb_touch = action_sets
+                    .get_action_bool(HAND_ACTION_SET, "thumb_touch")
+                    .ok()
+                    .and_then(|a| a.state(&session, path).ok())
+                    .is_some_and(|s| s.current_state);
+                pinch_strength_from_controller(index_value, thumb_touch)
+            }
+        };
+        let was_pinching = strength.is_pinching(hand);
+        *strength.get_mut(hand) = new_strength;
+        let pinching = match was_pinching {
+            true => new_strength > config.release_threshold,
+            false => new_strength >= config.threshold,
+        };
+        if pinching != was_pinching {
+            strength.pinching[hand as usize] = pinching;
+            pinch_events.send(PinchEvent {
+                hand,
+                pinching,
+                strength: new_strength,
+            });
+        }
+    }
+}
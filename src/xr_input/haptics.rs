@@ -0,0 +1,182 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{resources::XrSession, xr_init::xr_only};
+
+use super::{
+    actions::XrActionSets,
+    interactions::{InteractionEvent, XRInteractableState},
+    trackers::{OpenXRLeftController, OpenXRRightController},
+    Hand,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HapticPulse {
+    /// 0.0 to 1.0
+    pub amplitude: f32,
+    /// in seconds
+    pub duration: f32,
+    /// in hz, `None` lets the runtime pick
+    pub frequency: Option<f32>,
+}
+
+/// plays a pulse on the `haptic_feedback` action of the given hand
+pub fn apply_haptic_pulse(
+    action_sets: &XrActionSets,
+    session: &XrSession,
+    hand: Hand,
+    pulse: HapticPulse,
+) {
+    if let Err(err) = action_sets.apply_haptic_feedback(
+        session,
+        "oculus_input",
+        "haptic_feedback",
+        hand,
+        pulse.duration,
+        pulse.frequency,
+        pulse.amplitude,
+    ) {
+        warn!("unable to play haptic pulse: {}", err);
+    }
+}
+
+/// Pulses queued for the end of the frame, several pulses on one hand in the same frame
+/// are coalesced into the strongest one
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct Haptics {
+    left: Option<HapticPulse>,
+    right: Option<HapticPulse>,
+}
+
+impl Haptics {
+    /// queues a pulse, `amplitude` from 0.0 to 1.0 and `duration` in seconds
+    pub fn pulse(&mut self, hand: Hand, amplitude: f32, duration: f32) {
+        self.pulse_with(
+            hand,
+            HapticPulse {
+                amplitude,
+                duration,
+                frequency: None,
+            },
+        );
+    }
+
+    pub fn pulse_with(&mut self, hand: Hand, pulse: HapticPulse) {
+        let queued = match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        };
+        match queued {
+            Some(q) if q.amplitude >= pulse.amplitude => {}
+            _ => *queued = Some(pulse),
+        }
+    }
+}
+
+pub fn apply_queued_haptics(
+    mut haptics: ResMut<Haptics>,
+    action_sets: Res<XrActionSets>,
+    session: Res<XrSession>,
+) {
+    for (hand, pulse) in [
+        (Hand::Left, haptics.left.take()),
+        (Hand::Right, haptics.right.take()),
+    ] {
+        if let Some(pulse) = pulse {
+            apply_haptic_pulse(&action_sets, &session, hand, pulse);
+        }
+    }
+}
+
+/// pulses the hand of the interactor whenever an interactable changes state
+pub struct InteractionHapticsPlugin;
+
+impl Plugin for InteractionHapticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InteractionHaptics>();
+        app.add_systems(
+            Update,
+            route_interaction_haptics
+                .run_if(xr_only())
+                .run_if(|haptics: Res<InteractionHaptics>| haptics.enabled),
+        );
+    }
+}
+
+/// Pulses played by [`InteractionHapticsPlugin`], `None` plays nothing
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct InteractionHaptics {
+    pub enabled: bool,
+    pub hover: Option<HapticPulse>,
+    pub select: Option<HapticPulse>,
+}
+
+impl Default for InteractionHaptics {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hover: Some(HapticPulse {
+                amplitude: 0.2,
+                duration: 0.01,
+                frequency: None,
+            }),
+            select: Some(HapticPulse {
+                amplitude: 0.6,
+                duration: 0.03,
+                frequency: None,
+            }),
+        }
+    }
+}
+
+/// The interactor is taken from the event and its hand from either a [`Hand`] component
+/// or the controller it sits on, so every hand touching an interactable gets its own pulse.
+pub fn route_interaction_haptics(
+    haptics: Res<InteractionHaptics>,
+    mut events: EventReader<InteractionEvent>,
+    mut last_states: Local<HashMap<(Entity, Entity), XRInteractableState>>,
+    interactor_query: Query<(
+        Option<&Hand>,
+        Has<OpenXRLeftController>,
+        Has<OpenXRRightController>,
+    )>,
+    action_sets: Res<XrActionSets>,
+    session: Res<XrSession>,
+) {
+    let mut states: HashMap<(Entity, Entity), XRInteractableState> = HashMap::new();
+    for event in events.read() {
+        let state = states
+            .entry((event.interactor, event.interactable))
+            .or_insert(event.interactable_state);
+        if event.interactable_state > *state {
+            *state = event.interactable_state;
+        }
+    }
+    // only one pulse per hand and frame, the stronger one wins
+    let mut pulses: HashMap<Hand, XRInteractableState> = HashMap::new();
+    for ((interactor, interactable), state) in states.iter() {
+        if last_states.get(&(*interactor, *interactable)) == Some(state) {
+            continue;
+        }
+        let hand = match interactor_query.get(*interactor) {
+            Ok((Some(hand), _, _)) => *hand,
+            Ok((None, true, _)) => Hand::Left,
+            Ok((None, _, true)) => Hand::Right,
+            _ => continue,
+        };
+        let pulse = pulses.entry(hand).or_insert(*state);
+        if *state > *pulse {
+            *pulse = *state;
+        }
+    }
+    for (hand, state) in pulses {
+        let pulse = match state {
+            XRInteractableState::Idle => None,
+            XRInteractableState::Hover => haptics.hover,
+            XRInteractableState::Select => haptics.select,
+        };
+        if let Some(pulse) = pulse {
+            apply_haptic_pulse(&action_sets, &session, hand, pulse);
+        }
+    }
+    *last_states = states;
+}
@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+use super::{
+    smoothing::{smooth_transform, smoothing_alpha},
+    trackers::{OpenXRLeftEye, OpenXRRightEye, OpenXRTrackingRoot},
+};
+
+/// Keeps an entity, e.g. a quad with a ui texture, in front of the head for simple huds.
+/// The entity should not have a parent, its transform is set in world space.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct HeadLockedLayer {
+    /// distance in front of the head in meters
+    pub distance: f32,
+    /// time constant in seconds of the easing towards the head, see
+    /// [`HandSmoothing`](super::smoothing::HandSmoothing). 0.0 locks it rigidly to the head,
+    /// which tends to be uncomfortable, 0.2 to 0.5 feels like a lazy follow.
+    pub lazy_follow: f32,
+}
+
+impl Default for HeadLockedLayer {
+    fn default() -> Self {
+        Self {
+            distance: 1.5,
+            lazy_follow: 0.3,
+        }
+    }
+}
+
+/// the head pose in the tracking root, between the eyes
+pub fn head_pose(left_eye: &Transform, right_eye: &Transform) -> Transform {
+    Transform {
+        translation: left_eye.translation.lerp(right_eye.translation, 0.5),
+        rotation: left_eye.rotation.slerp(right_eye.rotation, 0.5),
+        scale: Vec3::ONE,
+    }
+}
+
+pub fn update_head_locked_layers(
+    time: Res<Time>,
+    root: Query<&Transform, With<OpenXRTrackingRoot>>,
+    left_eye: Query<&Transform, (With<OpenXRLeftEye>, Without<OpenXRRightEye>)>,
+    right_eye: Query<&Transform, (With<OpenXRRightEye>, Without<OpenXRLeftEye>)>,
+    mut layers: Query<
+        (Ref<HeadLockedLayer>, &mut Transform),
+        (
+            Without<OpenXRTrackingRoot>,
+            Without<OpenXRLeftEye>,
+            Without<OpenXRRightEye>,
+        ),
+    >,
+) {
+    let (Ok(left_eye), Ok(right_eye)) = (left_eye.get_single(), right_eye.get_single()) else {
+        return;
+    };
+    let root = root.get_single().copied().unwrap_or_default();
+    let head = root.mul_transform(head_pose(left_eye, right_eye));
+    for (layer, mut transform) in layers.iter_mut() {
+        let target = transform
+            .with_translation(head.translation + head.forward() * layer.distance)
+            .with_rotation(head.rotation);
+        // new layers start in front of the head instead of flying in
+        let alpha = match layer.is_added() {
+            true => 1.0,
+            false => smoothing_alpha(layer.lazy_follow, time.delta_seconds()),
+        };
+        *transform = smooth_transform(&transform, target, alpha);
+    }
+}
@@ -1,20 +1,42 @@
 pub mod actions;
+pub mod attachment;
+pub mod buttons;
+pub mod controller_input;
+pub mod controller_models;
 pub mod controllers;
 pub mod debug_gizmos;
+pub mod debug_overlay;
+pub mod dpad;
+pub mod eye_gaze;
+pub mod gestures;
 pub mod hand_poses;
 pub mod hands;
+pub mod haptics;
+pub mod head_locked;
 pub mod interactions;
 pub mod oculus_touch;
 pub mod prototype_locomotion;
+pub mod recenter;
+pub mod select_squeeze;
+pub mod smoothing;
+pub mod telemetry;
+pub mod tracked_devices;
 pub mod trackers;
+pub mod tuning;
 pub mod xr_camera;
 
 use crate::resources::{XrInstance, XrSession};
 use crate::xr_begin_frame;
-use crate::xr_init::{xr_only, XrPostSetup, XrSetup, XrPreSetup};
+use crate::xr_init::{
+    should_sync_actions, xr_focused, xr_input_active, xr_only, InputResumeSyncs, OnFocusLost,
+    XrFocused, XrInputReady, XrInputValid, XrPostSetup, XrPreSetup, XrSetup,
+};
 use crate::xr_input::controllers::XrControllerType;
 use crate::xr_input::oculus_touch::setup_oculus_controller;
-use crate::xr_input::xr_camera::{xr_camera_head_sync, Eye, XRProjection, XrCameraBundle};
+use crate::xr_input::xr_camera::{
+    update_eye_poses, update_eye_render_layers, xr_camera_head_sync, Eye, EyePoses, XRProjection,
+    XrCameraBundle, XrEyeRenderLayers,
+};
 use bevy::app::{App, PostUpdate, Startup};
 use bevy::ecs::entity::Entity;
 use bevy::ecs::query::With;
@@ -22,7 +44,9 @@ use bevy::ecs::system::Query;
 use bevy::log::{info, warn};
 use bevy::math::Vec2;
 use bevy::prelude::{BuildChildren, Component, Deref, DerefMut, IntoSystemConfigs, Resource};
-use bevy::prelude::{Commands, Plugin, PreUpdate, Quat, Res, SpatialBundle, Update, Vec3};
+use bevy::prelude::{
+    Commands, EventWriter, Local, Plugin, PreUpdate, Quat, Res, ResMut, SpatialBundle, Update, Vec3,
+};
 use bevy::render::camera::CameraProjectionPlugin;
 use bevy::render::view::{update_frusta, VisibilitySystems};
 use bevy::transform::TransformSystem;
@@ -30,9 +54,47 @@ use bevy::utils::HashMap;
 use openxr::Binding;
 
 use self::actions::{setup_oxr_actions, OpenXrActionsPlugin};
-use self::oculus_touch::{post_action_setup_oculus_controller, ActionSets, init_subaction_path};
+use self::attachment::{
+    propagate_attached_to_hand, update_attached_to_hand, update_hand_grip_points,
+    HandGripPointOffset,
+};
+use self::buttons::{
+    read_controller_buttons, update_controller_buttons, ControllerButtonChanged,
+    ControllerButtonReadings, ControllerButtons,
+};
+use self::controller_input::{update_controller_input, ControllerInput};
+use self::controller_models::{
+    swap_controller_models, update_interaction_profiles, ControllerModels,
+    ControllerProfileChanged, CurrentInteractionProfiles, XrInteractionProfileChanged,
+};
+use self::dpad::{update_stick_dpad, DpadEvent};
+use self::eye_gaze::{
+    setup_eye_gaze_action, setup_eye_gaze_space, update_gaze_pose, EyeGazeSupported, GazePose,
+};
+use self::gestures::{
+    update_controller_gestures, ControllerGesture, ControllerGestures, GestureConfig,
+};
+use self::hands::HandTrackingSourceChanged;
+use self::haptics::{apply_queued_haptics, Haptics};
+use self::head_locked::update_head_locked_layers;
+use self::oculus_touch::{init_subaction_path, post_action_setup_oculus_controller, ActionSets};
+use self::recenter::{
+    apply_reference_space, auto_recenter_on_focus, detect_system_recenter, handle_recenter,
+    handle_reference_space_change, AutoRecenterOnFocus, RecenterMode, RecenterSettings,
+    ReferenceSpaceChangePolicy, SystemRecenterDetected, XrRecenter, XrReferenceSpace,
+    XrReferenceSpaceChanged,
+};
+use self::select_squeeze::{setup_gameplay_action_set, update_select_squeeze, Grip, Trigger};
+use self::smoothing::{ControllerSmoothing, HandSmoothing};
+use self::telemetry::{
+    record_tracking_telemetry, telemetry_enabled, TrackingTelemetry, TrackingTelemetryConfig,
+};
+use self::tracked_devices::{
+    setup_tracked_device_actions, setup_tracked_device_spaces, update_tracked_devices,
+};
 use self::trackers::{
-    adopt_open_xr_trackers, update_open_xr_controllers, OpenXRLeftEye, OpenXRRightEye,
+    adopt_open_xr_trackers, locate_open_xr_controllers, update_open_xr_controllers,
+    update_pointer_rays, ControllerLocations, ControllerPoseOrigin, OpenXRLeftEye, OpenXRRightEye,
     OpenXRTrackingRoot,
 };
 
@@ -40,12 +102,22 @@ use self::trackers::{
 pub struct OpenXrInput {
     pub controller_type: XrControllerType,
 }
-#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Component)]
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
 pub enum Hand {
     Left,
     Right,
 }
 
+impl Hand {
+    /// the top level user path, e.g. `/user/hand/left`
+    pub fn user_path(self) -> &'static str {
+        match self {
+            Hand::Left => "/user/hand/left",
+            Hand::Right => "/user/hand/right",
+        }
+    }
+}
+
 impl OpenXrInput {
     pub fn new(controller_type: XrControllerType) -> Self {
         Self { controller_type }
@@ -64,19 +136,181 @@ impl Plugin for OpenXrInput {
         }
         //adopt any new trackers
         app.add_systems(PreUpdate, adopt_open_xr_trackers.run_if(xr_only()));
+        // runs while paused too, so the input gets invalidated when focus is lost
+        app.init_resource::<XrInputValid>();
+        app.init_resource::<InputResumeSyncs>();
+        app.add_event::<XrInputReady>();
         app.add_systems(PreUpdate, action_set_system.run_if(xr_only()));
         app.add_systems(
             PreUpdate,
             xr_camera_head_sync.run_if(xr_only()).after(xr_begin_frame),
         );
-        //update controller trackers
-        app.add_systems(Update, update_open_xr_controllers.run_if(xr_only()));
+        app.init_resource::<EyePoses>();
+        app.add_systems(
+            PreUpdate,
+            update_eye_poses.run_if(xr_only()).after(xr_begin_frame),
+        );
+        //state of every boolean action and of the controller inputs
+        app.init_resource::<ControllerButtons>();
+        app.init_resource::<ControllerButtonReadings>();
+        app.init_resource::<ControllerInput>();
+        app.add_event::<ControllerButtonChanged>();
+        app.add_systems(
+            PreUpdate,
+            (
+                (read_controller_buttons, update_controller_buttons).chain(),
+                update_controller_input,
+            )
+                .run_if(xr_only())
+                .run_if(xr_input_active)
+                .after(action_set_system),
+        );
+        //interaction profiles and the controller models shown for them
+        app.init_resource::<CurrentInteractionProfiles>();
+        app.init_resource::<ControllerModels>();
+        app.add_event::<XrInteractionProfileChanged>();
+        app.add_event::<ControllerProfileChanged>();
+        app.add_event::<HandTrackingSourceChanged>();
+        app.add_systems(
+            PreUpdate,
+            update_interaction_profiles
+                .run_if(xr_only())
+                .after(xr_begin_frame),
+        );
+        app.add_systems(Update, swap_controller_models.run_if(xr_only()));
+        //profile independent trigger and grip
+        app.init_resource::<Trigger>();
+        app.init_resource::<Grip>();
+        app.add_systems(XrSetup, setup_gameplay_action_set);
+        app.add_systems(
+            PreUpdate,
+            update_select_squeeze
+                .run_if(xr_only())
+                .run_if(xr_input_active)
+                .after(action_set_system),
+        );
+        //queued haptic pulses, played once per frame
+        app.init_resource::<Haptics>();
+        app.add_systems(
+            PostUpdate,
+            apply_queued_haptics
+                .run_if(xr_only())
+                .run_if(xr_input_active),
+        );
+        //reference space switching and recentering, handled before the next frame begins
+        app.init_resource::<RecenterSettings>();
+        app.init_resource::<AutoRecenterOnFocus>();
+        app.init_resource::<RecenterMode>();
+        app.init_resource::<SystemRecenterDetected>();
+        app.init_resource::<ReferenceSpaceChangePolicy>();
+        app.init_resource::<XrReferenceSpace>();
+        app.add_event::<XrRecenter>();
+        app.add_event::<XrReferenceSpaceChanged>();
+        app.add_systems(
+            PreUpdate,
+            (
+                (apply_reference_space, handle_recenter)
+                    .chain()
+                    .run_if(xr_only())
+                    .before(xr_begin_frame),
+                (
+                    detect_system_recenter,
+                    handle_reference_space_change,
+                    auto_recenter_on_focus,
+                )
+                    .run_if(xr_only())
+                    .after(xr_begin_frame),
+            ),
+        );
+        //tracking telemetry, off unless enabled in the config
+        app.init_resource::<TrackingTelemetryConfig>();
+        app.init_resource::<TrackingTelemetry>();
+        app.add_systems(
+            PreUpdate,
+            record_tracking_telemetry
+                .run_if(xr_only())
+                .run_if(telemetry_enabled)
+                .after(xr_begin_frame),
+        );
+        //controller velocities and swing/shake gestures
+        app.init_resource::<GestureConfig>();
+        app.init_resource::<ControllerGestures>();
+        app.add_event::<ControllerGesture>();
+        app.add_systems(
+            PreUpdate,
+            update_controller_gestures
+                .run_if(xr_only())
+                .run_if(xr_input_active)
+                .after(xr_begin_frame)
+                .after(action_set_system),
+        );
+        //thumbsticks as dpads, off unless StickAsDpad is inserted
+        app.add_event::<DpadEvent>();
+        app.add_systems(
+            PreUpdate,
+            update_stick_dpad
+                .run_if(xr_only())
+                .run_if(xr_input_active)
+                .after(action_set_system),
+        );
+        //frame rate independent smoothing, off by default
+        app.init_resource::<HandSmoothing>();
+        app.init_resource::<ControllerSmoothing>();
+        //update controller trackers, unfocused poses are stale so they hold the last one
+        app.init_resource::<ControllerPoseOrigin>();
+        app.init_resource::<ControllerLocations>();
+        app.add_systems(
+            Update,
+            (locate_open_xr_controllers, update_open_xr_controllers)
+                .chain()
+                .run_if(xr_focused()),
+        );
+        //combined eye gaze, only with XR_EXT_eye_gaze_interaction
+        app.init_resource::<EyeGazeSupported>();
+        app.init_resource::<GazePose>();
+        app.add_systems(XrSetup, setup_eye_gaze_action);
+        app.add_systems(XrPostSetup, setup_eye_gaze_space);
+        app.add_systems(
+            PreUpdate,
+            update_gaze_pose
+                .run_if(xr_focused())
+                .after(action_set_system),
+        );
+        //other tracked devices like vive trackers, none unless XrTrackedDevices is inserted
+        app.add_systems(XrSetup, setup_tracked_device_actions);
+        app.add_systems(XrPostSetup, setup_tracked_device_spaces);
+        app.add_systems(Update, update_tracked_devices.run_if(xr_focused()));
+        //per eye render layers
+        app.init_resource::<XrEyeRenderLayers>();
+        app.add_systems(Update, update_eye_render_layers.run_if(xr_only()));
+        //head locked huds, after the cameras were moved to the head
+        app.add_systems(Update, update_head_locked_layers.run_if(xr_only()));
         app.add_systems(
             PostUpdate,
             update_frusta::<XRProjection>
                 .after(TransformSystem::TransformPropagate)
                 .before(VisibilitySystems::UpdatePerspectiveFrusta),
         );
+        //move anything held in a hand
+        app.init_resource::<HandGripPointOffset>();
+        app.add_systems(
+            PostUpdate,
+            (
+                update_hand_grip_points,
+                update_attached_to_hand,
+                propagate_attached_to_hand,
+            )
+                .chain()
+                .run_if(xr_only())
+                .after(TransformSystem::TransformPropagate),
+        );
+        //world space pointer rays, once the root has its global transform
+        app.add_systems(
+            PostUpdate,
+            update_pointer_rays
+                .run_if(xr_only())
+                .after(TransformSystem::TransformPropagate),
+        );
         app.add_systems(XrPreSetup, init_subaction_path);
         app.add_systems(XrSetup, setup_xr_cameras);
     }
@@ -115,7 +349,25 @@ fn setup_xr_cameras(
     commands.entity(tracking_root).push_children(&[right, left]);
 }
 
-pub fn action_set_system(action_sets: Res<ActionSets>, session: Res<XrSession>) {
+pub fn action_set_system(
+    action_sets: Res<ActionSets>,
+    session: Res<XrSession>,
+    focused: Res<XrFocused>,
+    on_focus_lost: Res<OnFocusLost>,
+    resume_syncs: Res<InputResumeSyncs>,
+    mut input_valid: ResMut<XrInputValid>,
+    mut input_ready: EventWriter<XrInputReady>,
+    mut successful_syncs: Local<u32>,
+) {
+    // anything synced while unfocused is stale or zeroed
+    if !focused.0 {
+        input_valid.0 = false;
+        *successful_syncs = 0;
+    }
+    // not syncing keeps the action states of the last sync, so nothing new gets pressed
+    if !should_sync_actions(&focused, *on_focus_lost) {
+        return;
+    }
     let mut active_action_sets = vec![];
     for i in &action_sets.0 {
         active_action_sets.push(openxr::ActiveActionSet::new(i));
@@ -125,6 +377,13 @@ pub fn action_set_system(action_sets: Res<ActionSets>, session: Res<XrSession>)
         Err(err) => {
             warn!("{}", err);
         }
+        Ok(_) if focused.0 && !input_valid.0 => {
+            *successful_syncs += 1;
+            if *successful_syncs >= resume_syncs.0.max(1) {
+                input_valid.0 = true;
+                input_ready.send(XrInputReady);
+            }
+        }
         _ => {}
     }
 }
@@ -541,6 +541,128 @@ impl OculusController {
                 XrBinding::new("thumbrest_touch", "/user/hand/right/input/thumbrest/touch"),
             ],
         );
+        action_set.suggest_binding(
+            "/interaction_profiles/htc/vive_cosmos_controller",
+            &[
+                XrBinding::new("hand_pose", "/user/hand/left/input/grip/pose"),
+                XrBinding::new("hand_pose", "/user/hand/right/input/grip/pose"),
+                XrBinding::new("pointer_pose", "/user/hand/left/input/aim/pose"),
+                XrBinding::new("pointer_pose", "/user/hand/right/input/aim/pose"),
+                XrBinding::new("squeeze", "/user/hand/left/input/squeeze/click"),
+                XrBinding::new("squeeze", "/user/hand/right/input/squeeze/click"),
+                XrBinding::new("trigger", "/user/hand/left/input/trigger/value"),
+                XrBinding::new("trigger", "/user/hand/right/input/trigger/value"),
+                XrBinding::new("haptic_feedback", "/user/hand/left/output/haptic"),
+                XrBinding::new("haptic_feedback", "/user/hand/right/output/haptic"),
+                XrBinding::new("x_button", "/user/hand/left/input/x/click"),
+                XrBinding::new("y_button", "/user/hand/left/input/y/click"),
+                XrBinding::new("a_button", "/user/hand/right/input/a/click"),
+                XrBinding::new("b_button", "/user/hand/right/input/b/click"),
+                XrBinding::new("menu_button", "/user/hand/left/input/menu/click"),
+                XrBinding::new("thumbstick_x", "/user/hand/left/input/thumbstick/x"),
+                XrBinding::new("thumbstick_y", "/user/hand/left/input/thumbstick/y"),
+                XrBinding::new("thumbstick_x", "/user/hand/right/input/thumbstick/x"),
+                XrBinding::new("thumbstick_y", "/user/hand/right/input/thumbstick/y"),
+                XrBinding::new("thumbstick_click", "/user/hand/left/input/thumbstick/click"),
+                XrBinding::new(
+                    "thumbstick_click",
+                    "/user/hand/right/input/thumbstick/click",
+                ),
+                XrBinding::new("thumbstick_touch", "/user/hand/left/input/thumbstick/touch"),
+                XrBinding::new(
+                    "thumbstick_touch",
+                    "/user/hand/right/input/thumbstick/touch",
+                ),
+            ],
+        );
+        action_set.suggest_binding(
+            "/interaction_profiles/htc/vive_focus3_controller",
+            &[
+                XrBinding::new("hand_pose", "/user/hand/left/input/grip/pose"),
+                XrBinding::new("hand_pose", "/user/hand/right/input/grip/pose"),
+                XrBinding::new("pointer_pose", "/user/hand/left/input/aim/pose"),
+                XrBinding::new("pointer_pose", "/user/hand/right/input/aim/pose"),
+                XrBinding::new("squeeze", "/user/hand/left/input/squeeze/value"),
+                XrBinding::new("squeeze", "/user/hand/right/input/squeeze/value"),
+                XrBinding::new("trigger", "/user/hand/left/input/trigger/value"),
+                XrBinding::new("trigger", "/user/hand/right/input/trigger/value"),
+                XrBinding::new("trigger_touched", "/user/hand/left/input/trigger/touch"),
+                XrBinding::new("trigger_touched", "/user/hand/right/input/trigger/touch"),
+                XrBinding::new("haptic_feedback", "/user/hand/left/output/haptic"),
+                XrBinding::new("haptic_feedback", "/user/hand/right/output/haptic"),
+                XrBinding::new("x_button", "/user/hand/left/input/x/click"),
+                XrBinding::new("y_button", "/user/hand/left/input/y/click"),
+                XrBinding::new("a_button", "/user/hand/right/input/a/click"),
+                XrBinding::new("b_button", "/user/hand/right/input/b/click"),
+                XrBinding::new("menu_button", "/user/hand/left/input/menu/click"),
+                XrBinding::new("thumbstick_x", "/user/hand/left/input/thumbstick/x"),
+                XrBinding::new("thumbstick_y", "/user/hand/left/input/thumbstick/y"),
+                XrBinding::new("thumbstick_x", "/user/hand/right/input/thumbstick/x"),
+                XrBinding::new("thumbstick_y", "/user/hand/right/input/thumbstick/y"),
+                XrBinding::new("thumbstick_click", "/user/hand/left/input/thumbstick/click"),
+                XrBinding::new(
+                    "thumbstick_click",
+                    "/user/hand/right/input/thumbstick/click",
+                ),
+                XrBinding::new("thumbstick_touch", "/user/hand/left/input/thumbstick/touch"),
+                XrBinding::new(
+                    "thumbstick_touch",
+                    "/user/hand/right/input/thumbstick/touch",
+                ),
+                XrBinding::new("thumbrest_touch", "/user/hand/left/input/thumbrest/touch"),
+                XrBinding::new("thumbrest_touch", "/user/hand/right/input/thumbrest/touch"),
+            ],
+        );
+        // reverb g2, has no touch sensors and the menu button on both controllers
+        action_set.suggest_binding(
+            "/interaction_profiles/hp/mixed_reality_controller",
+            &[
+                XrBinding::new("hand_pose", "/user/hand/left/input/grip/pose"),
+                XrBinding::new("hand_pose", "/user/hand/right/input/grip/pose"),
+                XrBinding::new("pointer_pose", "/user/hand/left/input/aim/pose"),
+                XrBinding::new("pointer_pose", "/user/hand/right/input/aim/pose"),
+                XrBinding::new("squeeze", "/user/hand/left/input/squeeze/value"),
+                XrBinding::new("squeeze", "/user/hand/right/input/squeeze/value"),
+                XrBinding::new("trigger", "/user/hand/left/input/trigger/value"),
+                XrBinding::new("trigger", "/user/hand/right/input/trigger/value"),
+                XrBinding::new("haptic_feedback", "/user/hand/left/output/haptic"),
+                XrBinding::new("haptic_feedback", "/user/hand/right/output/haptic"),
+                XrBinding::new("x_button", "/user/hand/left/input/x/click"),
+                XrBinding::new("y_button", "/user/hand/left/input/y/click"),
+                XrBinding::new("a_button", "/user/hand/right/input/a/click"),
+                XrBinding::new("b_button", "/user/hand/right/input/b/click"),
+                XrBinding::new("menu_button", "/user/hand/left/input/menu/click"),
+                XrBinding::new("thumbstick_x", "/user/hand/left/input/thumbstick/x"),
+                XrBinding::new("thumbstick_y", "/user/hand/left/input/thumbstick/y"),
+                XrBinding::new("thumbstick_x", "/user/hand/right/input/thumbstick/x"),
+                XrBinding::new("thumbstick_y", "/user/hand/right/input/thumbstick/y"),
+                XrBinding::new("thumbstick_click", "/user/hand/left/input/thumbstick/click"),
+                XrBinding::new(
+                    "thumbstick_click",
+                    "/user/hand/right/input/thumbstick/click",
+                ),
+            ],
+        );
+        // the buttons are only bound above, but the poses work with any controller
+        for profile in [
+            "/interaction_profiles/valve/index_controller",
+            "/interaction_profiles/htc/vive_controller",
+            "/interaction_profiles/microsoft/motion_controller",
+            "/interaction_profiles/facebook/touch_controller_pro",
+            "/interaction_profiles/khr/simple_controller",
+        ] {
+            action_set.suggest_binding(
+                profile,
+                &[
+                    XrBinding::new("hand_pose", "/user/hand/left/input/grip/pose"),
+                    XrBinding::new("hand_pose", "/user/hand/right/input/grip/pose"),
+                    XrBinding::new("pointer_pose", "/user/hand/left/input/aim/pose"),
+                    XrBinding::new("pointer_pose", "/user/hand/right/input/aim/pose"),
+                    XrBinding::new("haptic_feedback", "/user/hand/left/output/haptic"),
+                    XrBinding::new("haptic_feedback", "/user/hand/right/output/haptic"),
+                ],
+            );
+        }
         Ok(this)
     }
 }
@@ -0,0 +1,258 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use openxr as xr;
+
+use crate::{
+    input::XrInput,
+    resources::{to_posef, XrFrameState, XrInstance, XrSession},
+    xr_init::XrFocusChanged,
+};
+
+use super::trackers::OpenXRTrackingRoot;
+
+use super::{QuatConv, Vec3Conv};
+
+/// Send to move the stage origin under the user and turn it to where they are looking
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct XrRecenter;
+
+#[derive(Resource, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecenterSettings {
+    /// keep the origin on the floor instead of moving it up to the head
+    pub preserve_height: bool,
+}
+
+impl Default for RecenterSettings {
+    fn default() -> Self {
+        Self {
+            preserve_height: true,
+        }
+    }
+}
+
+/// Recenter every time the session regains focus, e.g. when the headset is put back on
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AutoRecenterOnFocus(pub bool);
+
+/// Who recenters the user, so the app doesn't recenter on top of a system recenter
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecenterMode {
+    /// the system menu recenters, [`XrRecenter`] is ignored
+    System,
+    /// the app recenters with [`XrRecenter`]
+    App,
+    /// the app recenters until the runtime is seen recentering by itself
+    #[default]
+    Auto,
+}
+
+/// Sent by the runtime when it moved a reference space, e.g. because the user recentered
+/// from the system menu
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct XrReferenceSpaceChanged {
+    pub reference_space_type: xr::ReferenceSpaceType,
+    pub change_time: xr::Time,
+    /// the new origin in the previous one, `None` if the runtime doesn't know it
+    pub pose_in_previous_space: Option<Transform>,
+}
+
+/// What happens to the world when the runtime moves the reference space
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReferenceSpaceChangePolicy {
+    /// the world moves along with the new origin, like after [`XrRecenter`]
+    #[default]
+    Follow,
+    /// the tracking root is moved against the change so the world stays where it was
+    KeepWorld,
+}
+
+/// Which reference space the stage is created from, the stage is recreated when this changes.
+/// Use `Local` for seated experiences and `Stage` for room scale.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum XrReferenceSpace {
+    /// origin at the head when the app started, not on the floor
+    Local,
+    /// origin in the center of the play area on the floor
+    Stage,
+    /// like `Local` but on the floor, falls back to `Stage` without XR_EXT_local_floor
+    #[default]
+    LocalFloor,
+}
+
+impl XrReferenceSpace {
+    pub fn to_type(self) -> xr::ReferenceSpaceType {
+        match self {
+            XrReferenceSpace::Local => xr::ReferenceSpaceType::LOCAL,
+            XrReferenceSpace::Stage => xr::ReferenceSpaceType::STAGE,
+            XrReferenceSpace::LocalFloor => xr::ReferenceSpaceType::LOCAL_FLOOR_EXT,
+        }
+    }
+}
+
+/// Whether the runtime was seen recentering by itself, used by [`RecenterMode::Auto`]
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemRecenterDetected(pub bool);
+
+impl RecenterMode {
+    pub fn app_recenters(&self, system_recenter: SystemRecenterDetected) -> bool {
+        match self {
+            RecenterMode::System => false,
+            RecenterMode::App => true,
+            RecenterMode::Auto => !system_recenter.0,
+        }
+    }
+}
+
+/// Recreates the stage space so the head is at its origin, only turning around the up axis
+pub fn recenter_stage(
+    session: &XrSession,
+    xr_input: &mut XrInput,
+    time: xr::Time,
+    preserve_height: bool,
+) -> xr::Result<()> {
+    let head = xr_input.head.locate(&xr_input.stage, time)?;
+    if !head.location_flags.contains(
+        xr::SpaceLocationFlags::POSITION_VALID | xr::SpaceLocationFlags::ORIENTATION_VALID,
+    ) {
+        warn!("head is not tracked, not recentering");
+        return Ok(());
+    }
+    let mut position = head.pose.position.to_vec3();
+    if preserve_height {
+        position.y = 0.0;
+    }
+    let forward = head.pose.orientation.to_quat().mul_vec3(Vec3::NEG_Z);
+    let yaw = (-forward.x).atan2(-forward.z);
+    let local = Transform::from_translation(position).with_rotation(Quat::from_rotation_y(yaw));
+    let offset = xr_input.stage_offset.mul_transform(local);
+    let stage = session.create_reference_space(xr_input.stage_type, to_posef(offset))?;
+    xr_input.stage = Arc::new(stage);
+    xr_input.stage_offset = offset;
+    Ok(())
+}
+
+/// the first supported one of `space`, `Stage` and `Local`, every runtime has `Local`
+fn supported_reference_space_type(
+    session: &XrSession,
+    instance: &XrInstance,
+    space: XrReferenceSpace,
+) -> xr::ReferenceSpaceType {
+    // if the runtime can't list them, just try the wanted one
+    let supported = session.enumerate_reference_spaces().ok();
+    let wanted = space.to_type();
+    let usable = |ty: xr::ReferenceSpaceType| {
+        supported.as_ref().map_or(true, |s| s.contains(&ty))
+            && (ty != xr::ReferenceSpaceType::LOCAL_FLOOR_EXT
+                || instance.exts().ext_local_floor.is_some())
+    };
+    [wanted, xr::ReferenceSpaceType::STAGE]
+        .into_iter()
+        .find(|ty| usable(*ty))
+        .unwrap_or(xr::ReferenceSpaceType::LOCAL)
+}
+
+/// recreates the stage from [`XrReferenceSpace`] when it changed, dropping any recentering
+pub fn apply_reference_space(
+    space: Res<XrReferenceSpace>,
+    session: Res<XrSession>,
+    instance: Res<XrInstance>,
+    mut xr_input: ResMut<XrInput>,
+) {
+    if !space.is_changed() {
+        return;
+    }
+    let ty = supported_reference_space_type(&session, &instance, *space);
+    if ty != space.to_type() {
+        warn!(
+            "{:?} reference space is not supported, using {:?}",
+            *space, ty
+        );
+    }
+    if ty == xr_input.stage_type && xr_input.stage_offset == Transform::IDENTITY {
+        return;
+    }
+    match session.create_reference_space(ty, xr::Posef::IDENTITY) {
+        Ok(stage) => {
+            info!("using the {:?} reference space", ty);
+            xr_input.stage = Arc::new(stage);
+            xr_input.stage_type = ty;
+            xr_input.stage_offset = Transform::IDENTITY;
+        }
+        Err(err) => warn!("unable to create the {:?} reference space: {}", ty, err),
+    }
+}
+
+/// runs before the frame is begun, so the views of the next frame are already in the new stage
+pub fn handle_recenter(
+    mut requests: EventReader<XrRecenter>,
+    settings: Res<RecenterSettings>,
+    mode: Res<RecenterMode>,
+    system_recenter: Res<SystemRecenterDetected>,
+    session: Res<XrSession>,
+    frame_state: Res<XrFrameState>,
+    mut xr_input: ResMut<XrInput>,
+) {
+    if requests.read().count() == 0 {
+        return;
+    }
+    if !mode.app_recenters(*system_recenter) {
+        info!("the system handles recentering, ignoring recenter request");
+        return;
+    }
+    let time = frame_state.lock().unwrap().predicted_display_time;
+    if let Err(err) = recenter_stage(&session, &mut xr_input, time, settings.preserve_height) {
+        warn!("unable to recenter: {}", err);
+    }
+}
+
+pub fn auto_recenter_on_focus(
+    auto_recenter: Res<AutoRecenterOnFocus>,
+    mut focus_changed: EventReader<XrFocusChanged>,
+    mut recenter: EventWriter<XrRecenter>,
+) {
+    let gained_focus = focus_changed.read().any(|e| e.focused);
+    if auto_recenter.0 && gained_focus {
+        recenter.send(XrRecenter);
+    }
+}
+
+pub fn detect_system_recenter(
+    mut changes: EventReader<XrReferenceSpaceChanged>,
+    mut system_recenter: ResMut<SystemRecenterDetected>,
+) {
+    if changes.read().count() > 0 && !system_recenter.0 {
+        info!("the runtime recenters by itself, leaving recentering to it");
+        system_recenter.0 = true;
+    }
+}
+
+pub fn handle_reference_space_change(
+    policy: Res<ReferenceSpaceChangePolicy>,
+    xr_input: Res<XrInput>,
+    mut changes: EventReader<XrReferenceSpaceChanged>,
+    mut root: Query<&mut Transform, With<OpenXRTrackingRoot>>,
+) {
+    for change in changes.read() {
+        if change.reference_space_type != xr_input.stage_type {
+            continue;
+        }
+        info!(
+            "runtime moved the {:?} space by {:?}",
+            change.reference_space_type, change.pose_in_previous_space
+        );
+        let (ReferenceSpaceChangePolicy::KeepWorld, Some(pose)) =
+            (*policy, change.pose_in_previous_space)
+        else {
+            continue;
+        };
+        // the change applies at `change_time`, which is usually the next frame anyway
+        for mut root in root.iter_mut() {
+            *root = root.mul_transform(pose);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+
+use crate::resources::XrSession;
+
+use super::{
+    actions::{ActionHandednes, ActionType, SetupActionSets, XrActionSets, XrBinding},
+    oculus_touch::subaction_path,
+    Hand,
+};
+
+const GAMEPLAY_ACTION_SET: &str = "gameplay";
+
+/// Pull of the trigger, or the select button on controllers without one, 0.0 to 1.0
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Trigger {
+    pub left: f32,
+    pub right: f32,
+}
+
+/// Pull of the grip, 0.0 or 1.0 on controllers with a grip button, always 0.0 without a grip
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Grip {
+    pub left: f32,
+    pub right: f32,
+}
+
+impl Trigger {
+    pub fn value(&self, hand: Hand) -> f32 {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+    pub fn is_pressed(&self, hand: Hand, threshold: f32) -> bool {
+        self.value(hand) >= threshold
+    }
+}
+
+impl Grip {
+    pub fn value(&self, hand: Hand) -> f32 {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+    pub fn is_pressed(&self, hand: Hand, threshold: f32) -> bool {
+        self.value(hand) >= threshold
+    }
+}
+
+macro_rules! select_squeeze_bindings {
+    ($select:literal, $squeeze:literal) => {
+        [
+            XrBinding::new("select", concat!("/user/hand/left/input/", $select)),
+            XrBinding::new("select", concat!("/user/hand/right/input/", $select)),
+            XrBinding::new("squeeze", concat!("/user/hand/left/input/", $squeeze)),
+            XrBinding::new("squeeze", concat!("/user/hand/right/input/", $squeeze)),
+        ]
+    };
+}
+
+pub(crate) fn setup_gameplay_action_set(mut action_sets: ResMut<SetupActionSets>) {
+    let action_set = action_sets.add_action_set(GAMEPLAY_ACTION_SET, "Gameplay".into(), 0);
+    action_set.new_action(
+        "select",
+        "Select".into(),
+        ActionType::F32,
+        ActionHandednes::Double,
+    );
+    action_set.new_action(
+        "squeeze",
+        "Squeeze".into(),
+        ActionType::F32,
+        ActionHandednes::Double,
+    );
+
+    action_set.suggest_binding(
+        "/interaction_profiles/khr/simple_controller",
+        &[
+            XrBinding::new("select", "/user/hand/left/input/select/click"),
+            XrBinding::new("select", "/user/hand/right/input/select/click"),
+        ],
+    );
+    for profile in [
+        "/interaction_profiles/oculus/touch_controller",
+        "/interaction_profiles/valve/index_controller",
+        "/interaction_profiles/hp/mixed_reality_controller",
+        "/interaction_profiles/htc/vive_focus3_controller",
+        "/interaction_profiles/facebook/touch_controller_pro",
+    ] {
+        action_set.suggest_binding(
+            profile,
+            &select_squeeze_bindings!("trigger/value", "squeeze/value"),
+        );
+    }
+    // these only have a grip button
+    for profile in [
+        "/interaction_profiles/htc/vive_controller",
+        "/interaction_profiles/htc/vive_cosmos_controller",
+        "/interaction_profiles/microsoft/motion_controller",
+    ] {
+        action_set.suggest_binding(
+            profile,
+            &select_squeeze_bindings!("trigger/value", "squeeze/click"),
+        );
+    }
+}
+
+pub fn update_select_squeeze(
+    action_sets: Res<XrActionSets>,
+    session: Res<XrSession>,
+    mut trigger: ResMut<Trigger>,
+    mut grip: ResMut<Grip>,
+) {
+    let value = |action: &'static str, hand: Hand| {
+        action_sets
+            .get_action_f32(GAMEPLAY_ACTION_SET, action)
+            .ok()
+            .and_then(|a| a.state(&session, subaction_path(hand)).ok())
+            .map(|s| s.current_state)
+            .unwrap_or_default()
+    };
+    *trigger = Trigger {
+        left: value("select", Hand::Left),
+        right: value("select", Hand::Right),
+    };
+    *grip = Grip {
+        left: value("squeeze", Hand::Left),
+        right: value("squeeze", Hand::Right),
+    };
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+/// Smoothing of tracked hand joints, off by default.
+///
+/// `time_constant` is in seconds and doesn't depend on the refresh rate: after that long
+/// a joint has covered about 63% of the way to a new pose, after three times that about 95%.
+/// 0.0 disables smoothing, 0.02 to 0.05 removes jitter without noticeable lag.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandSmoothing {
+    pub time_constant: f32,
+}
+
+/// Smoothing of the controller grip and aim poses, works like [`HandSmoothing`]
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControllerSmoothing {
+    pub time_constant: f32,
+}
+
+/// Smoothing of the emulated finger curls and thumb pose, works like [`HandSmoothing`]
+/// but is on by default, as controller inputs jump straight from touched to not touched
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct CurlSmoothing {
+    pub time_constant: f32,
+}
+
+impl Default for CurlSmoothing {
+    fn default() -> Self {
+        Self {
+            time_constant: 0.04,
+        }
+    }
+}
+
+/// How far to move towards the target this frame, 1.0 means no smoothing
+pub fn smoothing_alpha(time_constant: f32, delta_seconds: f32) -> f32 {
+    if time_constant <= 0.0 {
+        return 1.0;
+    }
+    1.0 - (-delta_seconds / time_constant).exp()
+}
+
+/// moves `current` towards `target`, keeping the scale of `current`
+pub fn smooth_transform(current: &Transform, target: Transform, alpha: f32) -> Transform {
+    if alpha >= 1.0 {
+        return target.with_scale(current.scale);
+    }
+    Transform {
+        translation: current.translation.lerp(target.translation, alpha),
+        rotation: current.rotation.slerp(target.rotation, alpha),
+        scale: current.scale,
+    }
+}
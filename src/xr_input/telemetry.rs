@@ -0,0 +1,192 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use bevy::prelude::{Quat, Res, ResMut, Resource, Vec3};
+use openxr::{SpaceLocation, SpaceLocationFlags, Time};
+
+use crate::{
+    input::XrInput,
+    resources::{XrFrameState, XrSession},
+};
+
+use super::{actions::XrActionSets, oculus_touch::OculusController, Hand, QuatConv, Vec3Conv};
+
+/// Controls the tracking telemetry recorder, disabled by default.
+/// Flip `enabled` at runtime to start recording into [`TrackingTelemetry`].
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct TrackingTelemetryConfig {
+    pub enabled: bool,
+    /// samples per second
+    pub sample_rate: f32,
+    /// how many samples are kept before the oldest are dropped
+    pub capacity: usize,
+}
+
+impl Default for TrackingTelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rate: 90.0,
+            capacity: 90 * 60,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrackedPoseSample {
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub position_valid: bool,
+    pub orientation_valid: bool,
+    pub position_tracked: bool,
+    pub orientation_tracked: bool,
+}
+
+impl From<SpaceLocation> for TrackedPoseSample {
+    fn from(location: SpaceLocation) -> Self {
+        let flags = location.location_flags;
+        Self {
+            position: location.pose.position.to_vec3(),
+            rotation: location.pose.orientation.to_quat(),
+            position_valid: flags.contains(SpaceLocationFlags::POSITION_VALID),
+            orientation_valid: flags.contains(SpaceLocationFlags::ORIENTATION_VALID),
+            position_tracked: flags.contains(SpaceLocationFlags::POSITION_TRACKED),
+            orientation_tracked: flags.contains(SpaceLocationFlags::ORIENTATION_TRACKED),
+        }
+    }
+}
+
+/// One sample of the HMD and both controllers, in stage space
+#[derive(Clone, Copy, Debug)]
+pub struct TelemetrySample {
+    /// predicted display time of the frame in nanoseconds
+    pub time: i64,
+    pub hmd: TrackedPoseSample,
+    pub left: TrackedPoseSample,
+    pub right: TrackedPoseSample,
+}
+
+/// Ring buffer of recorded tracking samples
+#[derive(Resource, Default)]
+pub struct TrackingTelemetry {
+    samples: VecDeque<TelemetrySample>,
+    last_sample: Option<Time>,
+}
+
+impl TrackingTelemetry {
+    pub fn samples(&self) -> impl Iterator<Item = &TelemetrySample> {
+        self.samples.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.last_sample = None;
+    }
+
+    pub fn push(&mut self, sample: TelemetrySample, capacity: usize) {
+        while self.samples.len() >= capacity.max(1) {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// writes one row per device per sample
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "time_ns,device,px,py,pz,rx,ry,rz,rw,position_valid,orientation_valid,position_tracked,orientation_tracked"
+        )?;
+        for sample in &self.samples {
+            for (device, pose) in [
+                ("hmd", &sample.hmd),
+                ("left", &sample.left),
+                ("right", &sample.right),
+            ] {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                    sample.time,
+                    device,
+                    pose.position.x,
+                    pose.position.y,
+                    pose.position.z,
+                    pose.rotation.x,
+                    pose.rotation.y,
+                    pose.rotation.z,
+                    pose.rotation.w,
+                    pose.position_valid,
+                    pose.orientation_valid,
+                    pose.position_tracked,
+                    pose.orientation_tracked,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut writer)?;
+        writer.flush()
+    }
+}
+
+pub fn telemetry_enabled(config: Res<TrackingTelemetryConfig>) -> bool {
+    config.enabled
+}
+
+pub fn record_tracking_telemetry(
+    config: Res<TrackingTelemetryConfig>,
+    mut telemetry: ResMut<TrackingTelemetry>,
+    frame_state: Res<XrFrameState>,
+    xr_input: Res<XrInput>,
+    oculus_controller: Option<Res<OculusController>>,
+    action_sets: Option<Res<XrActionSets>>,
+    session: Res<XrSession>,
+) {
+    let frame_state = *frame_state.lock().unwrap();
+    let time = frame_state.predicted_display_time;
+    if config.sample_rate > 0.0 {
+        let period = (1_000_000_000.0 / config.sample_rate) as i64;
+        if let Some(last) = telemetry.last_sample {
+            if time.as_nanos() - last.as_nanos() < period {
+                return;
+            }
+        }
+    }
+    let hmd = xr_input
+        .head
+        .locate(&xr_input.stage, time)
+        .map(TrackedPoseSample::from)
+        .unwrap_or_default();
+    let (left, right) = match (oculus_controller, action_sets) {
+        (Some(controller), Some(action_sets)) => {
+            let controller = controller.get_ref(&session, &frame_state, &xr_input, &action_sets);
+            (
+                controller.grip_space(Hand::Left).0.into(),
+                controller.grip_space(Hand::Right).0.into(),
+            )
+        }
+        _ => Default::default(),
+    };
+    telemetry.last_sample = Some(time);
+    telemetry.push(
+        TelemetrySample {
+            time: time.as_nanos(),
+            hmd,
+            left,
+            right,
+        },
+        config.capacity,
+    );
+}
@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use openxr::{AnyGraphics, Posef, Session, Space, SpaceLocationFlags};
+
+use crate::{
+    input::XrInput,
+    resources::{XrFrameState, XrInstance, XrSession},
+};
+
+use super::{
+    actions::{ActionType, SetupActionSets, XrActionSets, XrBinding},
+    trackers::AimPose,
+    QuatConv, Vec3Conv,
+};
+
+pub const TRACKED_DEVICE_ACTION_SET: &str = "tracked_devices";
+
+/// How to bind the pose of a device that isn't a hand, e.g. a vive tracker on the waist
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrackedDeviceBinding {
+    /// top level path, e.g. `/user/vive_tracker_htcx/role/waist`
+    pub path: &'static str,
+    /// e.g. `/interaction_profiles/htc/vive_tracker_htcx`
+    pub interaction_profile: &'static str,
+    /// e.g. `/user/vive_tracker_htcx/role/waist/input/grip/pose`
+    pub grip: &'static str,
+    /// only read into [`AimPose`], most trackers don't have one
+    pub aim: Option<&'static str>,
+}
+
+/// Devices to create pose actions for, beyond the controllers and the headset.
+/// Has to be inserted before the session is set up, empty by default.
+/// Vive trackers need [`XrExtensions::enable_vive_tracker`](crate::graphics::extensions::XrExtensions::enable_vive_tracker).
+#[derive(Resource, Clone, Debug, Default)]
+pub struct XrTrackedDevices(pub Vec<TrackedDeviceBinding>);
+
+/// Follows the grip pose of the device at `path`, which has to be in [`XrTrackedDevices`].
+/// Add [`OpenXRTracker`](super::trackers::OpenXRTracker) too, the pose is relative to the root.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct OpenXRTrackedDevice {
+    pub path: String,
+}
+
+struct TrackedDeviceSpace {
+    path: &'static str,
+    grip: Space,
+    aim: Option<Space>,
+}
+
+#[derive(Resource, Default)]
+pub struct TrackedDeviceSpaces(Vec<TrackedDeviceSpace>);
+
+pub(crate) fn setup_tracked_device_actions(
+    devices: Option<Res<XrTrackedDevices>>,
+    mut action_sets: ResMut<SetupActionSets>,
+) {
+    let Some(devices) = devices.filter(|d| !d.0.is_empty()) else {
+        return;
+    };
+    let paths: Vec<&'static str> = devices.0.iter().map(|d| d.path).collect();
+    let action_set =
+        action_sets.add_action_set(TRACKED_DEVICE_ACTION_SET, "Tracked Devices".into(), 0);
+    action_set.new_action_with_subaction_paths(
+        "device_grip",
+        "Device Grip".into(),
+        ActionType::PoseF,
+        &paths,
+    );
+    // most trackers have no aim pose, an action without bindings would only be noise
+    let aim_paths: Vec<&'static str> = devices
+        .0
+        .iter()
+        .filter(|d| d.aim.is_some())
+        .map(|d| d.path)
+        .collect();
+    if !aim_paths.is_empty() {
+        action_set.new_action_with_subaction_paths(
+            "device_aim",
+            "Device Aim".into(),
+            ActionType::PoseF,
+            &aim_paths,
+        );
+    }
+    for device in devices.0.iter() {
+        action_set.suggest_binding(
+            device.interaction_profile,
+            &[XrBinding::new("device_grip", device.grip)],
+        );
+        if let Some(aim) = device.aim {
+            action_set.suggest_binding(
+                device.interaction_profile,
+                &[XrBinding::new("device_aim", aim)],
+            );
+        }
+    }
+}
+
+pub(crate) fn setup_tracked_device_spaces(
+    mut commands: Commands,
+    devices: Option<Res<XrTrackedDevices>>,
+    action_sets: Res<XrActionSets>,
+    instance: Res<XrInstance>,
+    session: Res<XrSession>,
+) {
+    let Some(devices) = devices.filter(|d| !d.0.is_empty()) else {
+        return;
+    };
+    let Ok(grip_action) = action_sets.get_action_posef(TRACKED_DEVICE_ACTION_SET, "device_grip")
+    else {
+        return;
+    };
+    // only there if a device has an aim pose
+    let aim_action = action_sets
+        .get_action_posef(TRACKED_DEVICE_ACTION_SET, "device_aim")
+        .ok();
+    let s = Session::<AnyGraphics>::clone(&session);
+    let mut spaces = Vec::new();
+    for device in devices.0.iter() {
+        let Ok(path) = instance.string_to_path(device.path) else {
+            warn!("{} is not a valid path for a tracked device", device.path);
+            continue;
+        };
+        let grip = match grip_action.create_space(s.clone(), path, Posef::IDENTITY) {
+            Ok(space) => space,
+            Err(err) => {
+                warn!("unable to create the space of {}: {}", device.path, err);
+                continue;
+            }
+        };
+        let aim = device.aim.and(aim_action).and_then(|aim_action| {
+            aim_action
+                .create_space(s.clone(), path, Posef::IDENTITY)
+                .ok()
+        });
+        spaces.push(TrackedDeviceSpace {
+            path: device.path,
+            grip,
+            aim,
+        });
+    }
+    commands.insert_resource(TrackedDeviceSpaces(spaces));
+}
+
+pub fn update_tracked_devices(
+    spaces: Option<Res<TrackedDeviceSpaces>>,
+    frame_state: Res<XrFrameState>,
+    xr_input: Res<XrInput>,
+    mut devices: Query<(&OpenXRTrackedDevice, &mut Transform, Option<&mut AimPose>)>,
+) {
+    let Some(spaces) = spaces else {
+        return;
+    };
+    let time = frame_state.lock().unwrap().predicted_display_time;
+    let locate = |space: &Space| {
+        let location = space.locate(&xr_input.stage, time).ok()?;
+        let valid = SpaceLocationFlags::POSITION_VALID | SpaceLocationFlags::ORIENTATION_VALID;
+        // a device that lost tracking keeps its last pose
+        location.location_flags.contains(valid).then(|| {
+            Transform::from_translation(location.pose.position.to_vec3())
+                .with_rotation(location.pose.orientation.to_quat())
+        })
+    };
+    for (device, mut transform, aim_pose) in devices.iter_mut() {
+        let Some(space) = spaces.0.iter().find(|s| s.path == device.path) else {
+            continue;
+        };
+        if let Some(grip) = locate(&space.grip) {
+            // keep the scale of the entity
+            *transform = transform
+                .with_translation(grip.translation)
+                .with_rotation(grip.rotation);
+        }
+        if let (Some(mut aim_pose), Some(aim)) = (aim_pose, space.aim.as_ref().and_then(locate)) {
+            aim_pose.0 = aim;
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use bevy::log::{debug, info};
+use openxr::{SpaceLocationFlags, SpaceVelocityFlags};
+use std::f32::consts::FRAC_PI_2;
+
 use bevy::prelude::{
-    Added, BuildChildren, Commands, Component, Entity, Query, Res, Transform, Vec3, With, Without,
+    BuildChildren, Commands, Component, Entity, GlobalTransform, Has, Or, Parent, Quat, Query, Ray,
+    Res, ResMut, Resource, Time, Transform, Vec3, With, Without,
 };
 
 use crate::{
@@ -8,10 +12,17 @@ use crate::{
     resources::{XrFrameState, XrSession},
 };
 
-use super::{actions::XrActionSets, oculus_touch::OculusController, Hand, QuatConv, Vec3Conv};
+use super::{
+    actions::XrActionSets,
+    oculus_touch::OculusController,
+    smoothing::{smooth_transform, smoothing_alpha, ControllerSmoothing},
+    Hand, QuatConv, Vec3Conv,
+};
 
 #[derive(Component)]
 pub struct OpenXRTrackingRoot;
+/// Parented to the [`OpenXRTrackingRoot`], so its transform is relative to the root.
+/// The hand bones are not trackers, they are in world space with the root already applied.
 #[derive(Component)]
 pub struct OpenXRTracker;
 #[derive(Component)]
@@ -29,8 +40,63 @@ pub struct OpenXRController;
 #[derive(Component)]
 pub struct AimPose(pub Transform);
 
+/// World space ray along the [`AimPose`], add it to a controller for laser pointers and UI.
+/// Updated after the transforms were propagated, the origin is moved `origin_offset`
+/// meters forward, e.g. to start at the tip of the controller.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PointerRay {
+    pub origin: Vec3,
+    pub direction: Vec3,
+    pub origin_offset: f32,
+}
+
+impl PointerRay {
+    pub fn with_offset(origin_offset: f32) -> Self {
+        Self {
+            origin_offset,
+            ..Default::default()
+        }
+    }
+
+    pub fn ray(&self) -> Ray {
+        Ray {
+            origin: self.origin,
+            direction: self.direction,
+        }
+    }
+}
+
+pub fn update_pointer_rays(
+    tracking_root_query: Query<&GlobalTransform, With<OpenXRTrackingRoot>>,
+    mut pointers: Query<(&AimPose, &mut PointerRay)>,
+) {
+    let Ok(root) = tracking_root_query.get_single() else {
+        return;
+    };
+    for (aim, mut pointer) in pointers.iter_mut() {
+        let aim = root.mul_transform(aim.0);
+        pointer.direction = aim.forward();
+        pointer.origin = aim.translation() + pointer.direction * pointer.origin_offset;
+    }
+}
+
+/// On controllers whose pose the runtime currently can't locate, e.g. turned off or out of view.
+/// Their transform keeps the last tracked pose instead of snapping to the origin.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct TrackingLost;
+
+/// Linear velocity in m/s, only updated if the component is present and the runtime
+/// reports a valid one, otherwise the last valid value stays.
+/// Controllers report it in the tracking root, hand bones in world space.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinearVelocity(pub Vec3);
+/// Angular velocity in rad/s as axis times speed, like [`LinearVelocity`]
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct AngularVelocity(pub Vec3);
+
+/// trackers spawned before the root are adopted as soon as it exists
 pub fn adopt_open_xr_trackers(
-    query: Query<Entity, Added<OpenXRTracker>>,
+    query: Query<Entity, (With<OpenXRTracker>, Without<Parent>)>,
     mut commands: Commands,
     tracking_root_query: Query<(Entity, With<OpenXRTrackingRoot>)>,
 ) {
@@ -43,98 +109,294 @@ pub fn adopt_open_xr_trackers(
                 commands.entity(thing.0).add_child(tracker);
             }
         }
-        Err(_) => info!("root isnt spawned yet?"),
+        Err(_) if !query.is_empty() => debug!("root isnt spawned yet?"),
+        Err(_) => {}
     }
 }
 
-pub fn update_open_xr_controllers(
+/// Which pose drives the transform of the controller entities, the other poses are still
+/// available through the [`GripPose`], [`AimPose`] and [`PalmPose`] components
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControllerPoseOrigin {
+    /// where the controller is held, the default
+    #[default]
+    Grip,
+    /// pointing forward from the controller, e.g. for laser pointers
+    Aim,
+    /// the center of the palm, see [`palm_pose`]
+    Palm,
+}
+
+/// the grip pose of a controller in the tracking root, only updated if the component is present
+#[derive(Component)]
+pub struct GripPose(pub Transform);
+/// the palm pose of a controller in the tracking root, only updated if the component is present
+#[derive(Component)]
+pub struct PalmPose(pub Transform);
+
+/// how far the center of the palm is from the grip pose
+pub const PALM_OFFSET: f32 = 0.035;
+
+/// Approximates the palm from the grip pose: moved towards the palm along the grip x axis
+/// and turned so -Z points out of the palm
+pub fn palm_pose(grip: Transform, hand: Hand) -> Transform {
+    let side = match hand {
+        Hand::Left => -1.0,
+        Hand::Right => 1.0,
+    };
+    let rotation = grip.rotation * Quat::from_rotation_y(side * FRAC_PI_2);
+    Transform {
+        translation: grip.translation + grip.rotation * Vec3::new(side * PALM_OFFSET, 0.0, 0.0),
+        rotation,
+        scale: Vec3::ONE,
+    }
+}
+
+/// Where the runtime located a controller this frame, in the tracking root
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ControllerLocation {
+    pub grip: Transform,
+    pub aim: Transform,
+    /// the grip velocities, `None` if the runtime reported no valid one
+    pub linear_velocity: Option<Vec3>,
+    pub angular_velocity: Option<Vec3>,
+}
+
+impl ControllerLocation {
+    /// a controller without velocities and aiming along its grip
+    pub fn from_grip(grip: Transform) -> Self {
+        Self {
+            grip,
+            aim: grip,
+            linear_velocity: None,
+            angular_velocity: None,
+        }
+    }
+}
+
+/// The controller locations [`update_open_xr_controllers`] moves the controller entities to,
+/// written by [`locate_open_xr_controllers`] every focused frame.
+/// `None` means the runtime can't locate that controller.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ControllerLocations {
+    pub left: Option<ControllerLocation>,
+    pub right: Option<ControllerLocation>,
+}
+
+impl ControllerLocations {
+    pub fn get(&self, hand: Hand) -> Option<ControllerLocation> {
+        match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        }
+    }
+
+    pub fn set(&mut self, hand: Hand, location: Option<ControllerLocation>) {
+        match hand {
+            Hand::Left => self.left = location,
+            Hand::Right => self.right = location,
+        }
+    }
+}
+
+pub fn locate_open_xr_controllers(
     oculus_controller: Res<OculusController>,
-    mut left_controller_query: Query<(
-        &mut Transform,
-        Option<&mut AimPose>,
-        With<OpenXRLeftController>,
-        Without<OpenXRRightController>,
-    )>,
-    mut right_controller_query: Query<(
-        &mut Transform,
-        Option<&mut AimPose>,
-        With<OpenXRRightController>,
-        Without<OpenXRLeftController>,
-    )>,
     frame_state: Res<XrFrameState>,
     xr_input: Res<XrInput>,
     session: Res<XrSession>,
     action_sets: Res<XrActionSets>,
+    mut locations: ResMut<ControllerLocations>,
 ) {
     //lock dat frame?
     let frame_state = *frame_state.lock().unwrap();
     //get controller
     let controller = oculus_controller.get_ref(&session, &frame_state, &xr_input, &action_sets);
-    //get left controller
-    let left_grip_space = controller.grip_space(Hand::Left);
-    let left_aim_space = controller.aim_space(Hand::Left);
-    let left_postion = left_grip_space.0.pose.position.to_vec3();
-    //TODO figure out how to not get the entity multiple times
-    let left_aim_pose = left_controller_query.get_single_mut();
-    //set aim pose
-    match left_aim_pose {
-        Ok(left_entity) => match left_entity.1 {
-            Some(mut pose) => {
-                *pose = AimPose(Transform {
-                    translation: left_aim_space.0.pose.position.to_vec3(),
-                    rotation: left_aim_space.0.pose.orientation.to_quat(),
-                    scale: Vec3::splat(1.0),
-                });
-            }
-            None => (),
-        },
-        Err(_) => debug!("no left controlelr entity found"),
+    let to_transform = |location: openxr::SpaceLocation| Transform {
+        translation: location.pose.position.to_vec3(),
+        rotation: location.pose.orientation.to_quat(),
+        scale: Vec3::splat(1.0),
+    };
+    for hand in [Hand::Left, Hand::Right] {
+        let (grip_location, velocity) = controller.grip_space(hand);
+        let valid = SpaceLocationFlags::POSITION_VALID | SpaceLocationFlags::ORIENTATION_VALID;
+        let location = grip_location
+            .location_flags
+            .contains(valid)
+            .then(|| ControllerLocation {
+                grip: to_transform(grip_location),
+                aim: to_transform(controller.aim_space(hand).0),
+                linear_velocity: velocity
+                    .velocity_flags
+                    .contains(SpaceVelocityFlags::LINEAR_VALID)
+                    .then(|| velocity.linear_velocity.to_vec3()),
+                angular_velocity: velocity
+                    .velocity_flags
+                    .contains(SpaceVelocityFlags::ANGULAR_VALID)
+                    .then(|| velocity.angular_velocity.to_vec3()),
+            });
+        locations.set(hand, location);
     }
-    //set translation
-    let left_translation = left_controller_query.get_single_mut();
-    match left_translation {
-        Ok(mut left_entity) => left_entity.0.translation = left_postion,
-        Err(_) => (),
+}
+
+pub fn update_open_xr_controllers(
+    mut commands: Commands,
+    locations: Res<ControllerLocations>,
+    mut controller_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            Has<OpenXRLeftController>,
+            Option<&mut GripPose>,
+            Option<&mut AimPose>,
+            Option<&mut PalmPose>,
+            Option<&mut LinearVelocity>,
+            Option<&mut AngularVelocity>,
+            Has<TrackingLost>,
+        ),
+        Or<(With<OpenXRLeftController>, With<OpenXRRightController>)>,
+    >,
+    time: Res<Time>,
+    smoothing: Res<ControllerSmoothing>,
+    pose_origin: Res<ControllerPoseOrigin>,
+) {
+    let alpha = smoothing_alpha(smoothing.time_constant, time.delta_seconds());
+    if controller_query.is_empty() {
+        debug!("no controller entity found");
     }
-    //set rotation
-    let left_rotataion = left_controller_query.get_single_mut();
-    match left_rotataion {
-        Ok(mut left_entity) => {
-            left_entity.0.rotation = left_grip_space.0.pose.orientation.to_quat()
+    for (
+        entity,
+        mut transform,
+        is_left,
+        grip_pose,
+        aim_pose,
+        palm_pose_component,
+        linear_velocity,
+        angular_velocity,
+        tracking_lost,
+    ) in controller_query.iter_mut()
+    {
+        let hand = match is_left {
+            true => Hand::Left,
+            false => Hand::Right,
+        };
+        let location = match (locations.get(hand), tracking_lost) {
+            (None, false) => {
+                debug!("lost tracking of the {:?} controller", hand);
+                commands.entity(entity).insert(TrackingLost);
+                continue;
+            }
+            (None, true) => continue,
+            (Some(location), true) => {
+                commands.entity(entity).remove::<TrackingLost>();
+                location
+            }
+            (Some(location), false) => location,
+        };
+        let ControllerLocation {
+            grip,
+            aim,
+            linear_velocity: location_linear,
+            angular_velocity: location_angular,
+        } = location;
+        // the grip velocity, whichever pose drives the transform
+        if let (Some(mut linear), Some(velocity)) = (linear_velocity, location_linear) {
+            linear.0 = velocity;
         }
-        Err(_) => (),
-    }
-    //get right controller
-    let right_grip_space = controller.grip_space(Hand::Right);
-    let right_aim_space = controller.aim_space(Hand::Right);
-    let right_postion = right_grip_space.0.pose.position.to_vec3();
-
-    let right_aim_pose = right_controller_query.get_single_mut();
-    match right_aim_pose {
-        Ok(right_entity) => match right_entity.1 {
-            Some(mut pose) => {
-                *pose = AimPose(Transform {
-                    translation: right_aim_space.0.pose.position.to_vec3(),
-                    rotation: right_aim_space.0.pose.orientation.to_quat(),
-                    scale: Vec3::splat(1.0),
-                });
+        if let (Some(mut angular), Some(velocity)) = (angular_velocity, location_angular) {
+            angular.0 = velocity;
+        }
+        let palm = palm_pose(grip, hand);
+        match grip_pose {
+            Some(mut pose) => *pose = GripPose(smooth_transform(&pose.0, grip, alpha)),
+            // hand emulation needs the grip when the transform is something else
+            None if *pose_origin != ControllerPoseOrigin::Grip => {
+                commands.entity(entity).insert(GripPose(grip));
             }
-            None => (),
-        },
-        Err(_) => debug!("no right controlelr entity found"),
+            None => {}
+        }
+        if let Some(mut pose) = aim_pose {
+            *pose = AimPose(smooth_transform(&pose.0, aim, alpha));
+        }
+        if let Some(mut pose) = palm_pose_component {
+            *pose = PalmPose(smooth_transform(&pose.0, palm, alpha));
+        }
+        let target = match *pose_origin {
+            ControllerPoseOrigin::Grip => grip,
+            ControllerPoseOrigin::Aim => aim,
+            ControllerPoseOrigin::Palm => palm,
+        };
+        // keep the scale of the entity
+        let target = transform
+            .with_translation(target.translation)
+            .with_rotation(target.rotation);
+        *transform = smooth_transform(&transform, target, alpha);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::{App, Update};
+
+    fn controller_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        app.init_resource::<ControllerSmoothing>();
+        app.init_resource::<ControllerPoseOrigin>();
+        app.init_resource::<ControllerLocations>();
+        app.add_systems(Update, update_open_xr_controllers);
+        app
     }
-    //set translation
-    let right_translation = right_controller_query.get_single_mut();
-    match right_translation {
-        Ok(mut right_entity) => right_entity.0.translation = right_postion,
-        Err(_) => (),
+
+    fn locate(app: &mut App, hand: Hand, grip: Option<Transform>) {
+        app.world
+            .resource_mut::<ControllerLocations>()
+            .set(hand, grip.map(ControllerLocation::from_grip));
     }
-    //set rotation
-    let right_rotataion = right_controller_query.get_single_mut();
-    match right_rotataion {
-        Ok(mut right_entity) => {
-            right_entity.0.rotation = right_grip_space.0.pose.orientation.to_quat()
-        }
-        Err(_) => (),
+
+    #[test]
+    fn lost_tracking_keeps_the_last_pose() {
+        let mut app = controller_app();
+        let controller = app
+            .world
+            .spawn((Transform::default(), OpenXRLeftController))
+            .id();
+        let tracked = Transform::from_xyz(-0.2, 1.0, -0.3);
+        locate(&mut app, Hand::Left, Some(tracked));
+        app.update();
+        assert_eq!(*app.world.get::<Transform>(controller).unwrap(), tracked);
+
+        locate(&mut app, Hand::Left, None);
+        app.update();
+        assert!(app.world.get::<TrackingLost>(controller).is_some());
+        assert_eq!(*app.world.get::<Transform>(controller).unwrap(), tracked);
+        // still lost, still where it was last seen
+        app.update();
+        assert_eq!(*app.world.get::<Transform>(controller).unwrap(), tracked);
+
+        let found = Transform::from_xyz(-0.1, 1.2, -0.4);
+        locate(&mut app, Hand::Left, Some(found));
+        app.update();
+        assert!(app.world.get::<TrackingLost>(controller).is_none());
+        assert_eq!(*app.world.get::<Transform>(controller).unwrap(), found);
+    }
+
+    #[test]
+    fn lost_tracking_only_affects_that_controller() {
+        let mut app = controller_app();
+        let left = app
+            .world
+            .spawn((Transform::default(), OpenXRLeftController))
+            .id();
+        let right = app
+            .world
+            .spawn((Transform::default(), OpenXRRightController))
+            .id();
+        let tracked = Transform::from_xyz(0.2, 1.0, -0.3);
+        locate(&mut app, Hand::Right, Some(tracked));
+        app.update();
+        assert!(app.world.get::<TrackingLost>(left).is_some());
+        assert!(app.world.get::<TrackingLost>(right).is_none());
+        assert_eq!(*app.world.get::<Transform>(right).unwrap(), tracked);
     }
 }
@@ -0,0 +1,41 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+/// Hooks for persisting a tuning resource like [`HandGripPointOffset`](super::attachment::HandGripPointOffset)
+/// or [`HandSmoothing`](super::smoothing::HandSmoothing), with the `serialize` feature they
+/// implement `Serialize` and `Deserialize` so apps can store them however they like.
+///
+/// - load: insert the saved resource before adding the xr plugins, they don't overwrite it
+/// - save: read [`TuningChanged<T>`], sent whenever the resource changed after startup
+pub struct PersistTuningPlugin<T>(PhantomData<T>);
+
+impl<T> Default for PersistTuningPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Resource + Clone> Plugin for PersistTuningPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TuningChanged<T>>();
+        app.add_systems(Last, send_tuning_changed::<T>);
+    }
+}
+
+/// The new value of a tuning resource, see [`PersistTuningPlugin`]
+#[derive(Event, Clone, Debug)]
+pub struct TuningChanged<T: Resource>(pub T);
+
+fn send_tuning_changed<T: Resource + Clone>(
+    tuning: Option<Res<T>>,
+    mut changed: EventWriter<TuningChanged<T>>,
+) {
+    let Some(tuning) = tuning else {
+        return;
+    };
+    // the value it was loaded or initialized with doesn't need saving
+    if tuning.is_changed() && !tuning.is_added() {
+        changed.send(TuningChanged(tuning.clone()));
+    }
+}
@@ -1,11 +1,13 @@
+use crate::resources::{XrClipPlanes, XrRenderScale, XrResolution};
 use crate::xr_input::{QuatConv, Vec3Conv};
 use crate::{LEFT_XR_TEXTURE_HANDLE, RIGHT_XR_TEXTURE_HANDLE};
 use bevy::core_pipeline::tonemapping::{DebandDither, Tonemapping};
 use bevy::math::Vec3A;
 use bevy::prelude::*;
-use bevy::render::camera::{CameraProjection, CameraRenderGraph, RenderTarget};
+use bevy::render::camera::{CameraProjection, CameraRenderGraph, RenderTarget, Viewport};
 use bevy::render::primitives::Frustum;
-use bevy::render::view::{ColorGrading, VisibleEntities};
+use bevy::render::render_resource::TextureUsages;
+use bevy::render::view::{ColorGrading, RenderLayers, VisibleEntities};
 use openxr::Fovf;
 
 #[derive(Bundle)]
@@ -54,6 +56,40 @@ pub enum Eye {
     Right = 1,
 }
 
+/// Render layers of each eye camera, so content can be shown to one eye only,
+/// e.g. for calibration patterns or stereo effects.
+/// Defaults to the default layer for both eyes, like cameras without render layers.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct XrEyeRenderLayers {
+    pub left: RenderLayers,
+    pub right: RenderLayers,
+}
+
+impl XrEyeRenderLayers {
+    pub fn get(&self, eye: Eye) -> RenderLayers {
+        match eye {
+            Eye::Left => self.left,
+            Eye::Right => self.right,
+        }
+    }
+}
+
+pub fn update_eye_render_layers(
+    mut commands: Commands,
+    layers: Res<XrEyeRenderLayers>,
+    cameras: Query<(Entity, &XrCameraType, Option<&RenderLayers>)>,
+) {
+    for (entity, camera_type, current) in cameras.iter() {
+        let XrCameraType::Xr(eye) = camera_type else {
+            continue;
+        };
+        let wanted = layers.get(*eye);
+        if current != Some(&wanted) {
+            commands.entity(entity).insert(wanted);
+        }
+    }
+}
+
 impl XrCameraBundle {
     pub fn new(eye: Eye) -> Self {
         Self {
@@ -72,7 +108,12 @@ impl XrCameraBundle {
             frustum: Default::default(),
             transform: Default::default(),
             global_transform: Default::default(),
-            camera_3d: Default::default(),
+            camera_3d: Camera3d {
+                // copied into the depth swapchain when the runtime takes depth
+                depth_texture_usages: (TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC)
+                    .into(),
+                ..default()
+            },
             tonemapping: Default::default(),
             dither: DebandDither::Enabled,
             color_grading: Default::default(),
@@ -92,9 +133,10 @@ pub struct XRProjection {
 
 impl Default for XRProjection {
     fn default() -> Self {
+        let planes = XrClipPlanes::default();
         Self {
-            near: 0.1,
-            far: 1000.,
+            near: planes.near,
+            far: planes.far,
             fov: Default::default(),
         }
     }
@@ -106,6 +148,95 @@ impl XRProjection {
     }
 }
 
+/// Scales the field of view of both eyes down, e.g. for comfort or to save fill rate at the edges.
+/// The submitted views use the same scaled fov, so the image still lines up with the world.
+/// Clamped to `MIN_FOV_SCALE..=1.0`, anything below 1.0 reduces peripheral vision.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct FovScale(pub f32);
+
+impl Default for FovScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+pub const MIN_FOV_SCALE: f32 = 0.3;
+
+impl FovScale {
+    pub fn clamped(&self) -> f32 {
+        self.0.clamp(MIN_FOV_SCALE, 1.0)
+    }
+}
+
+/// scales the tangents of the asymmetric fov, which keeps the center of the projection in place
+pub fn scale_fov(fov: Fovf, scale: f32) -> Fovf {
+    let scale_angle = |angle: f32| (angle.tan() * scale).atan();
+    Fovf {
+        angle_left: scale_angle(fov.angle_left),
+        angle_right: scale_angle(fov.angle_right),
+        angle_up: scale_angle(fov.angle_up),
+        angle_down: scale_angle(fov.angle_down),
+    }
+}
+
+pub fn warn_fov_scale(fov_scale: Res<FovScale>) {
+    if !fov_scale.is_changed() || fov_scale.0 >= 1.0 {
+        return;
+    }
+    if fov_scale.clamped() != fov_scale.0 {
+        warn!(
+            "fov scale {} is out of range, clamping to {}",
+            fov_scale.0,
+            fov_scale.clamped()
+        );
+    }
+    warn!(
+        "rendering with a fov scale of {}, this reduces peripheral vision",
+        fov_scale.clamped()
+    );
+}
+
+pub fn apply_clip_planes(planes: Res<XrClipPlanes>, mut projections: Query<&mut XRProjection>) {
+    for mut projection in projections.iter_mut() {
+        if projection.near != planes.near || projection.far != planes.far {
+            projection.near = planes.near;
+            projection.far = planes.far;
+        }
+    }
+}
+
+/// renders the eyes into the region of the swapchain image given by [`XrRenderScale`]
+pub fn apply_render_scale(
+    render_scale: Res<XrRenderScale>,
+    resolution: Res<XrResolution>,
+    mut cameras: Query<(&mut Camera, &XrCameraType)>,
+) {
+    let size = render_scale.scaled(**resolution);
+    let viewport = match size == **resolution {
+        true => None,
+        false => Some(Viewport {
+            physical_position: UVec2::ZERO,
+            physical_size: size,
+            ..default()
+        }),
+    };
+    for (mut camera, camera_type) in cameras.iter_mut() {
+        if !matches!(camera_type, XrCameraType::Xr(_)) {
+            continue;
+        }
+        let current = camera
+            .viewport
+            .as_ref()
+            .map(|v| (v.physical_position, v.physical_size));
+        let new = viewport
+            .as_ref()
+            .map(|v| (v.physical_position, v.physical_size));
+        if current != new {
+            camera.viewport = viewport.clone();
+        }
+    }
+}
+
 impl CameraProjection for XRProjection {
     // =============================================================================
     // math code adapted from
@@ -123,8 +254,11 @@ impl CameraProjection for XRProjection {
         let fov = self.fov;
         let is_vulkan_api = false; // FIXME wgpu probably abstracts this
         let near_z = self.near;
-        let far_z = -1.; //   use infinite proj
-                         // let far_z = self.far;
+        // an infinite far plane uses the infinite projection
+        let far_z = match self.far.is_finite() {
+            true => self.far,
+            false => -1.,
+        };
 
         let tan_angle_left = fov.angle_left.tan();
         let tan_angle_right = fov.angle_right.tan();
@@ -187,7 +321,7 @@ impl CameraProjection for XRProjection {
 
             return z_reversal * Mat4::from_cols_array(&cols);
         } else {
-            // normal projection
+            // normal projection, with near and far swapped for bevy's reverse z
             cols[0] = 2. / tan_angle_width;
             cols[4] = 0.;
             cols[8] = (tan_angle_right + tan_angle_left) / tan_angle_width;
@@ -200,8 +334,8 @@ impl CameraProjection for XRProjection {
 
             cols[2] = 0.;
             cols[6] = 0.;
-            cols[10] = -(far_z + offset_z) / (far_z - near_z);
-            cols[14] = -(far_z * (near_z + offset_z)) / (far_z - near_z);
+            cols[10] = (near_z + offset_z) / (far_z - near_z);
+            cols[14] = (far_z * (near_z + offset_z)) / (far_z - near_z);
 
             cols[3] = 0.;
             cols[7] = 0.;
@@ -215,7 +349,8 @@ impl CameraProjection for XRProjection {
     fn update(&mut self, _width: f32, _height: f32) {}
 
     fn far(&self) -> f32 {
-        self.far
+        // the frustum can't have a far plane at infinity
+        self.far.min(f32::MAX)
     }
 
     fn get_frustum_corners(&self, z_near: f32, z_far: f32) -> [Vec3A; 8] {
@@ -239,24 +374,68 @@ impl CameraProjection for XRProjection {
     }
 }
 
+/// Pose of each eye relative to the tracking root, from the located views
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EyePoses {
+    pub left: Transform,
+    pub right: Transform,
+}
+
+impl EyePoses {
+    pub fn get(&self, eye: Eye) -> Transform {
+        match eye {
+            Eye::Left => self.left,
+            Eye::Right => self.right,
+        }
+    }
+
+    /// the distance between the eyes
+    pub fn ipd_meters(&self) -> f32 {
+        self.left.translation.distance(self.right.translation)
+    }
+
+    /// halfway between the eyes, e.g. for a reticle
+    pub fn center(&self) -> Transform {
+        Transform::from_translation(self.left.translation.lerp(self.right.translation, 0.5))
+            .with_rotation(self.left.rotation.slerp(self.right.rotation, 0.5))
+    }
+}
+
+pub fn update_eye_poses(views: Res<crate::resources::XrViews>, mut poses: ResMut<EyePoses>) {
+    let v = views.lock().unwrap();
+    let [left, right, ..] = v.as_slice() else {
+        return;
+    };
+    let to_transform = |view: &openxr::View| {
+        Transform::from_translation(view.pose.position.to_vec3())
+            .with_rotation(view.pose.orientation.to_quat())
+    };
+    let new = EyePoses {
+        left: to_transform(left),
+        right: to_transform(right),
+    };
+    if *poses != new {
+        *poses = new;
+    }
+}
+
 pub fn xr_camera_head_sync(
     views: ResMut<crate::resources::XrViews>,
     mut query: Query<(&mut Transform, &XrCameraType, &mut XRProjection)>,
 ) {
-    let mut f = || -> Option<()> {
-        //TODO calculate HMD position
-        for (mut transform, camera_type, mut xr_projection) in query.iter_mut() {
-            let view_idx = match camera_type {
-                XrCameraType::Xr(eye) => *eye as usize,
-                XrCameraType::Flatscreen => return None,
-            };
-            let v = views.lock().unwrap();
-            let view = v.get(view_idx)?;
-            xr_projection.fov = view.fov;
-            transform.rotation = view.pose.orientation.to_quat();
-            transform.translation = view.pose.position.to_vec3();
-        }
-        Some(())
-    };
-    let _ = f();
+    let v = views.lock().unwrap();
+    for (mut transform, camera_type, mut xr_projection) in query.iter_mut() {
+        let view_idx = match camera_type {
+            XrCameraType::Xr(eye) => *eye as usize,
+            // a flatscreen camera must not stop the eyes after it from updating
+            XrCameraType::Flatscreen => continue,
+        };
+        let Some(view) = v.get(view_idx) else {
+            continue;
+        };
+        // the projection is built from the asymmetric fov of each view
+        xr_projection.fov = view.fov;
+        transform.rotation = view.pose.orientation.to_quat();
+        transform.translation = view.pose.position.to_vec3();
+    }
 }